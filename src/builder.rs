@@ -0,0 +1,440 @@
+use std::time::Duration;
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Proxy, Url,
+};
+
+use crate::{
+    cache::{disk::DiskCache, ResponseCache},
+    client::{ClientInner, CoCClient},
+    deserialization::DeserializationMode,
+    drift::SchemaDriftDetector,
+    errors::CoCClientError,
+    rate_limit::{RateLimit, RateLimiter},
+    retry::RetryPolicy,
+    tokens::TokenPool,
+    vcr::Cassette,
+};
+
+/// The URL of the official Clash of Clans API.
+pub const DEFAULT_BASE_URL: &str = "https://api.clashofclans.com";
+
+/// The version of the Clash of Clans API used by default.
+pub const DEFAULT_VERSION: &str = "v1";
+
+/// The user agent sent when none is configured.
+const DEFAULT_USER_AGENT: &str = concat!("rust_of_clans/", env!("CARGO_PKG_VERSION"));
+
+/// Builds a `CoCClient` with a custom configuration.
+///
+/// The bearer token is sent with every request, including when a prebuilt `Client` is supplied.
+#[derive(Debug)]
+pub struct CoCClientBuilder {
+    bearer_token: String,
+    base_url: String,
+    version: String,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: String,
+    default_headers: HeaderMap,
+    proxy: Option<String>,
+    http_client: Option<Client>,
+    token_pool: Option<TokenPool>,
+    rate_limit: Option<RateLimit>,
+    retry_policy: Option<RetryPolicy>,
+    cache_capacity: Option<usize>,
+    disk_cache: Option<DiskCache>,
+    cassette: Option<Cassette>,
+    keep_raw_body: bool,
+    deserialization_mode: DeserializationMode,
+    detect_schema_drift: bool,
+}
+
+impl CoCClientBuilder {
+    /// Creates a builder targeting the official API with the given bearer token.
+    pub fn new(bearer_token: impl Into<String>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            base_url: String::from(DEFAULT_BASE_URL),
+            version: String::from(DEFAULT_VERSION),
+            connect_timeout: None,
+            timeout: None,
+            user_agent: String::from(DEFAULT_USER_AGENT),
+            default_headers: HeaderMap::new(),
+            proxy: None,
+            http_client: None,
+            token_pool: None,
+            rate_limit: None,
+            retry_policy: None,
+            cache_capacity: None,
+            disk_cache: None,
+            cassette: None,
+            keep_raw_body: false,
+            deserialization_mode: DeserializationMode::default(),
+            detect_schema_drift: false,
+        }
+    }
+
+    /// Creates a builder targeting the official API that distributes the requests across the keys of `token_pool`.
+    pub fn with_token_pool(token_pool: TokenPool) -> Self {
+        Self::new(String::new()).token_pool(token_pool)
+    }
+
+    /// Sets the base URL of the API, e.g. a community proxy or a local stand-in server.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the version of the API, e.g. `v1`.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Sets the timeout for establishing a connection.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sets the timeout for a whole request, from connecting until the body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Adds a header sent with every request.
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Adds headers sent with every request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    /// Routes every request through the HTTP proxy at the given URL.
+    pub fn proxy(mut self, proxy_url: impl Into<String>) -> Self {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    /// Uses a prebuilt `Client` for the requests.
+    ///
+    /// Timeouts, user agent, default headers and proxy are part of the prebuilt client,
+    /// so the corresponding settings of this builder are ignored.
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Distributes the requests across the keys of `token_pool` instead of the single bearer token.
+    pub fn token_pool(mut self, token_pool: TokenPool) -> Self {
+        self.token_pool = Some(token_pool);
+        self
+    }
+
+    /// Limits the requests sent with each key, queueing requests once the burst is used up.
+    ///
+    /// The rate of a key is lowered when the API throttles it, and recovers with each successful request.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Retries failed requests according to `retry_policy`.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Caches up to `capacity` successful responses in memory for as long as their `Cache-Control` header allows.
+    ///
+    /// Least recently used responses are evicted first once the cache is full.
+    pub fn response_cache(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Stores successful responses in `disk_cache`, and serves them flagged as stale while the API is unavailable.
+    ///
+    /// When combined with `response_cache`, the in-memory cache is consulted first.
+    pub fn disk_cache(mut self, disk_cache: DiskCache) -> Self {
+        self.disk_cache = Some(disk_cache);
+        self
+    }
+
+    /// Records every request sent to the API into `cassette`, or answers requests from it without network access.
+    ///
+    /// Replayed responses still go through the caches, the retry policy and deserialization as usual.
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Keeps the raw body in every `ApiResponse`, e.g. for logging or archiving.
+    pub fn keep_raw_body(mut self, keep_raw_body: bool) -> Self {
+        self.keep_raw_body = keep_raw_body;
+        self
+    }
+
+    /// Sets how responses that do not match the models are deserialized, `Standard` by default.
+    ///
+    /// Use `DeserializationMode::Strict` in tests to fail loudly when the API changes, or
+    /// `DeserializationMode::Lenient` to also keep working when the API stops sending fields.
+    pub fn deserialization_mode(mut self, deserialization_mode: DeserializationMode) -> Self {
+        self.deserialization_mode = deserialization_mode;
+        self
+    }
+
+    /// Compares every response against its model, see `CoCClient::schema_drift_detector`.
+    ///
+    /// Each response is deserialized twice, so this is meant for monitoring rather than hot paths.
+    pub fn detect_schema_drift(mut self, detect_schema_drift: bool) -> Self {
+        self.detect_schema_drift = detect_schema_drift;
+        self
+    }
+
+    /// Builds the `CoCClient`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured `CoCClient`, or a `CoCClientError` if the base URL or proxy URL
+    /// is invalid or the HTTP client could not be created.
+    pub fn build(self) -> Result<CoCClient, CoCClientError> {
+        let base_url = self.base_url.trim_end_matches('/').to_string();
+
+        Url::parse(&base_url).map_err(|err| {
+            CoCClientError::InvalidConfiguration(format!(
+                "invalid base URL {:?}: {}",
+                base_url, err
+            ))
+        })?;
+
+        let version = self.version.trim_matches('/').to_string();
+
+        let url = if version.is_empty() {
+            base_url.clone()
+        } else {
+            format!("{}/{}", base_url, version)
+        };
+
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut client_builder = Client::builder()
+                    .user_agent(self.user_agent)
+                    .default_headers(self.default_headers);
+
+                if let Some(connect_timeout) = self.connect_timeout {
+                    client_builder = client_builder.connect_timeout(connect_timeout);
+                }
+
+                if let Some(timeout) = self.timeout {
+                    client_builder = client_builder.timeout(timeout);
+                }
+
+                if let Some(proxy_url) = self.proxy {
+                    let proxy = Proxy::all(&proxy_url).map_err(|err| {
+                        CoCClientError::InvalidConfiguration(format!(
+                            "invalid proxy URL {:?}: {}",
+                            proxy_url, err
+                        ))
+                    })?;
+                    client_builder = client_builder.proxy(proxy);
+                }
+
+                client_builder.build().map_err(CoCClientError::Request)?
+            }
+        };
+
+        let tokens = self
+            .token_pool
+            .unwrap_or_else(|| TokenPool::new([self.bearer_token]));
+
+        if let Some(rate_limit) = &self.rate_limit {
+            let rates = [
+                rate_limit.requests_per_second,
+                rate_limit.min_requests_per_second,
+            ];
+            if rates.iter().any(|rate| !rate.is_finite() || *rate <= 0.0) {
+                return Err(CoCClientError::InvalidConfiguration(String::from(
+                    "rate limits must be positive numbers of requests per second",
+                )));
+            }
+
+            if rate_limit.min_requests_per_second > rate_limit.requests_per_second {
+                return Err(CoCClientError::InvalidConfiguration(String::from(
+                    "the minimum rate limit must not exceed the rate limit",
+                )));
+            }
+
+            if !rate_limit.recovery_step.is_finite() || rate_limit.recovery_step <= 0.0 {
+                return Err(CoCClientError::InvalidConfiguration(String::from(
+                    "the rate limit recovery step must be a positive number",
+                )));
+            }
+        }
+
+        let rate_limiter = self
+            .rate_limit
+            .map(|rate_limit| RateLimiter::new(rate_limit, tokens.keys().len()));
+
+        Ok(CoCClient::from_inner(ClientInner {
+            base_url,
+            tokens,
+            rate_limiter,
+            retry_policy: self.retry_policy,
+            cache: self.cache_capacity.map(ResponseCache::new),
+            disk_cache: self.disk_cache,
+            cassette: self.cassette,
+            keep_raw_body: self.keep_raw_body,
+            deserialization_mode: self.deserialization_mode,
+            drift_detector: self.detect_schema_drift.then(SchemaDriftDetector::new),
+            client,
+            url,
+            version,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header;
+
+    use super::*;
+    use crate::testing::{MockResponse, MockServer};
+
+    #[test]
+    fn builder_defaults() {
+        let coc_client = CoCClientBuilder::new("MY_BEARER_TOKEN").build().unwrap();
+
+        assert_eq!(coc_client.base_url(), DEFAULT_BASE_URL);
+        assert_eq!(coc_client.version(), DEFAULT_VERSION);
+        assert_eq!(coc_client.url(), "https://api.clashofclans.com/v1");
+    }
+
+    #[test]
+    fn builder_custom_base_url_and_version() {
+        let coc_client = CoCClientBuilder::new("MY_BEARER_TOKEN")
+            .base_url("http://localhost:8080/")
+            .version("v2")
+            .build()
+            .unwrap();
+
+        assert_eq!(coc_client.base_url(), "http://localhost:8080");
+        assert_eq!(coc_client.url(), "http://localhost:8080/v2");
+    }
+
+    #[test]
+    fn builder_rejects_invalid_urls() {
+        let invalid_base_url = CoCClientBuilder::new("MY_BEARER_TOKEN")
+            .base_url("not a url")
+            .build();
+
+        assert!(matches!(
+            invalid_base_url,
+            Err(CoCClientError::InvalidConfiguration(_))
+        ));
+
+        let invalid_proxy = CoCClientBuilder::new("MY_BEARER_TOKEN")
+            .proxy("not a url")
+            .build();
+
+        assert!(matches!(
+            invalid_proxy,
+            Err(CoCClientError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn builder_rejects_invalid_rate_limit() {
+        let invalid = [
+            RateLimit::per_second(0.0),
+            RateLimit::per_second(10.0).min_requests_per_second(20.0),
+            RateLimit::per_second(10.0).recovery_step(-0.1),
+            RateLimit::per_second(10.0).recovery_step(f64::NAN),
+        ];
+
+        for rate_limit in invalid {
+            let coc_client = CoCClientBuilder::new("MY_BEARER_TOKEN")
+                .rate_limit(rate_limit)
+                .build();
+
+            assert!(
+                matches!(coc_client, Err(CoCClientError::InvalidConfiguration(_))),
+                "{:?}",
+                rate_limit
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn builder_sends_configured_headers() {
+        let server = MockServer::start_with_handler(|_| MockResponse::json(200, "{}")).await;
+
+        let coc_client = CoCClientBuilder::new("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .user_agent("my-bot/1.0")
+            .default_header(
+                HeaderName::from_static("x-custom"),
+                HeaderValue::from_static("custom"),
+            )
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        let path = format!("{}/goldpass/seasons/current", coc_client.url());
+        coc_client
+            .send_get_request_with_query(&path, Vec::new())
+            .await
+            .unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(request.path, "/v1/goldpass/seasons/current");
+        assert_eq!(
+            request.header("authorization"),
+            Some("Bearer MY_BEARER_TOKEN")
+        );
+        assert_eq!(request.header("user-agent"), Some("my-bot/1.0"));
+        assert_eq!(request.header("x-custom"), Some("custom"));
+    }
+
+    #[tokio::test]
+    async fn builder_with_http_client_still_sends_bearer_token() {
+        let server = MockServer::start_with_handler(|_| MockResponse::json(200, "{}")).await;
+
+        let mut headers = header::HeaderMap::new();
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("prebuilt"));
+        let client = Client::builder().default_headers(headers).build().unwrap();
+
+        let coc_client = CoCClientBuilder::new("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .http_client(client)
+            .build()
+            .unwrap();
+
+        let path = format!("{}/goldpass/seasons/current", coc_client.url());
+        coc_client
+            .send_get_request_with_query(&path, Vec::new())
+            .await
+            .unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(
+            request.header("authorization"),
+            Some("Bearer MY_BEARER_TOKEN")
+        );
+        assert_eq!(request.header("user-agent"), Some("prebuilt"));
+    }
+}
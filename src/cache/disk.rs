@@ -0,0 +1,543 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::client::ClientResponse;
+
+use super::max_age;
+
+/// The version of the on-disk format. Entries written with another version are ignored.
+pub const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// The prefix of the subdirectories holding the entries, followed by the format version.
+const VERSION_DIRECTORY_PREFIX: &str = "rust_of_clans-cache-v";
+
+/// Represents the hit and miss counters of a `DiskCache`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskCacheStats {
+    /// The number of requests answered with a fresh entry.
+    pub hits: u64,
+    /// The number of requests answered with an expired entry because the API was unavailable.
+    pub stale_hits: u64,
+    /// The number of requests without a fresh entry.
+    pub misses: u64,
+    /// The number of responses that could not be written to disk.
+    pub write_errors: u64,
+}
+
+/// The number of temporary files created by this process, so concurrent writes never share one.
+static TEMPORARY_FILES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize, Debug)]
+struct DiskEntry {
+    version: u32,
+    key: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    expires_at: u64,
+}
+
+/// Represents an entry read from a `DiskCache`.
+pub(crate) enum Cached {
+    /// The entry can be served without contacting the API.
+    Fresh(ClientResponse),
+    /// The entry has expired, but may be served while the API is unavailable.
+    Stale(ClientResponse),
+}
+
+/// Represents a cache of successful responses stored on disk, so it survives restarts.
+///
+/// Entries are fresh for as long as their `Cache-Control: max-age` allows. Expired entries are
+/// revalidated with the API, and served flagged as stale if the API is in maintenance (503)
+/// or cannot be reached, for up to `max_stale` after they expired.
+///
+/// Entries are stored in a subdirectory named `rust_of_clans-cache-v{CACHE_FORMAT_VERSION}`, one
+/// file per request. Subdirectories of other format versions are deleted the first time the cache
+/// is used, and entries that can no longer be served are deleted when they are read. Nothing else
+/// in the directory is touched.
+#[derive(Debug)]
+pub struct DiskCache {
+    directory: PathBuf,
+    max_stale: Duration,
+    stats: Mutex<DiskCacheStats>,
+    last_write_error: Mutex<Option<String>>,
+    pruned: AtomicBool,
+}
+
+impl DiskCache {
+    /// Creates a cache storing its entries under `directory`.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into().join(format!(
+                "{}{}",
+                VERSION_DIRECTORY_PREFIX, CACHE_FORMAT_VERSION
+            )),
+            max_stale: Duration::from_secs(24 * 60 * 60),
+            stats: Mutex::new(DiskCacheStats::default()),
+            last_write_error: Mutex::new(None),
+            pruned: AtomicBool::new(false),
+        }
+    }
+
+    /// Sets how long after expiring an entry may still be served while the API is unavailable.
+    pub fn max_stale(mut self, max_stale: Duration) -> Self {
+        self.max_stale = max_stale;
+        self
+    }
+
+    /// Returns the directory holding the entries of the current format version.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the hit and miss counters of the cache.
+    pub fn stats(&self) -> DiskCacheStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Returns the last error that prevented a response from being written to disk, if any.
+    pub fn last_write_error(&self) -> Option<String> {
+        self.last_write_error.lock().unwrap().clone()
+    }
+
+    /// Deletes every stored entry.
+    pub async fn clear(&self) -> io::Result<()> {
+        match tokio::fs::remove_dir_all(&self.directory).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads the entry stored under `key`, counting a hit or a miss.
+    ///
+    /// Entries that cannot be read, were written by another format version, or expired more
+    /// than `max_stale` before `now` are treated as missing.
+    pub(crate) async fn load(&self, key: &str, now: SystemTime) -> Option<Cached> {
+        self.prune_other_versions().await;
+
+        let cached = self.read(key, now).await;
+
+        let mut stats = self.stats.lock().unwrap();
+        match cached {
+            Some(Cached::Fresh(_)) => stats.hits += 1,
+            _ => stats.misses += 1,
+        }
+
+        cached
+    }
+
+    /// Counts a stale entry served in place of a failed request.
+    pub(crate) fn record_stale_hit(&self) {
+        self.stats.lock().unwrap().stale_hits += 1;
+    }
+
+    /// Stores `response` under `key` if it is successful and allows caching.
+    ///
+    /// # Returns
+    ///
+    /// Returns the error that prevented the entry from being written, which is also counted in
+    /// `stats` and kept as `last_write_error`.
+    pub(crate) async fn store(
+        &self,
+        key: &str,
+        response: &ClientResponse,
+        now: SystemTime,
+    ) -> io::Result<()> {
+        if !response.status_code.is_success() {
+            return Ok(());
+        }
+
+        let Some(max_age) = max_age(&response.headers) else {
+            return Ok(());
+        };
+
+        self.prune_other_versions().await;
+
+        let entry = DiskEntry {
+            version: CACHE_FORMAT_VERSION,
+            key: key.to_string(),
+            status: response.status_code.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body: response.text.clone(),
+            expires_at: unix_seconds(now + max_age),
+        };
+
+        let result = self.write(key, &entry).await;
+
+        if let Err(err) = &result {
+            self.stats.lock().unwrap().write_errors += 1;
+            *self.last_write_error.lock().unwrap() = Some(format!(
+                "cannot write {}: {}",
+                self.path(key).display(),
+                err
+            ));
+        }
+
+        result
+    }
+
+    /// Deletes the subdirectories of other format versions, once per cache.
+    async fn prune_other_versions(&self) {
+        if self.pruned.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let Some(parent) = self.directory.parent() else {
+            return;
+        };
+        let Ok(mut entries) = tokio::fs::read_dir(parent).await else {
+            return;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name();
+            let is_version = name.to_str().is_some_and(|name| {
+                name.strip_prefix(VERSION_DIRECTORY_PREFIX)
+                    .is_some_and(|version| {
+                        !version.is_empty() && version.bytes().all(|byte| byte.is_ascii_digit())
+                    })
+            });
+
+            if is_version && entry.path() != self.directory {
+                let _ = tokio::fs::remove_dir_all(entry.path()).await;
+            }
+        }
+    }
+
+    async fn read(&self, key: &str, now: SystemTime) -> Option<Cached> {
+        let path = self.path(key);
+        let contents = tokio::fs::read(&path).await.ok()?;
+
+        let Ok(entry) = serde_json::from_slice::<DiskEntry>(&contents) else {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        };
+
+        if entry.key != key {
+            return None;
+        }
+
+        let now = unix_seconds(now);
+        if entry.version != CACHE_FORMAT_VERSION
+            || now >= entry.expires_at.saturating_add(self.max_stale.as_secs())
+        {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &entry.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        if let Some(max_age) = max_age(&headers) {
+            let remaining = entry.expires_at.saturating_sub(now);
+            let age = max_age.as_secs().saturating_sub(remaining);
+            headers.insert(AGE, HeaderValue::from(age));
+        }
+
+        let fresh = now < entry.expires_at;
+        let response = ClientResponse {
+            text: entry.body,
+            status_code: StatusCode::from_u16(entry.status).ok()?,
+            headers,
+            stale: !fresh,
+            deserialization_mode: Default::default(),
+            drift: None,
+        };
+
+        Some(if fresh {
+            Cached::Fresh(response)
+        } else {
+            Cached::Stale(response)
+        })
+    }
+
+    async fn write(&self, key: &str, entry: &DiskEntry) -> io::Result<()> {
+        tokio::fs::create_dir_all(&self.directory).await?;
+
+        let path = self.path(key);
+        let temporary = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let contents = serde_json::to_vec(entry)?;
+
+        tokio::fs::write(&temporary, contents).await?;
+        tokio::fs::rename(&temporary, &path).await
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{:016x}.json", fnv1a(key)))
+    }
+}
+
+/// Hashes `key` with 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust versions.
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::CACHE_CONTROL;
+
+    use super::*;
+    use crate::client::CoCClient;
+    use crate::testing::{MockResponse, MockServer};
+
+    const GOLD_PASS_BODY: &str =
+        r#"{"startTime":"20230601T080000.000Z","endTime":"20230701T080000.000Z"}"#;
+
+    fn temporary_directory(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        std::env::temp_dir().join(format!(
+            "rust_of_clans_{}_{}_{}",
+            name,
+            std::process::id(),
+            nanos
+        ))
+    }
+
+    fn response(body: &str, cache_control: &str) -> ClientResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_str(cache_control).unwrap());
+
+        ClientResponse {
+            text: body.to_string(),
+            status_code: StatusCode::OK,
+            headers,
+            stale: false,
+            deserialization_mode: Default::default(),
+            drift: None,
+        }
+    }
+
+    fn hours_ago(hours: u64) -> SystemTime {
+        SystemTime::now() - Duration::from_secs(hours * 60 * 60)
+    }
+
+    #[tokio::test]
+    async fn entries_are_fresh_then_stale_then_gone() {
+        let cache =
+            DiskCache::new(temporary_directory("expiry")).max_stale(Duration::from_secs(7200));
+        cache
+            .store("/a", &response("a", "max-age=3600"), SystemTime::now())
+            .await
+            .unwrap();
+        cache
+            .store("/b", &response("b", "max-age=3600"), hours_ago(2))
+            .await
+            .unwrap();
+        cache
+            .store("/c", &response("c", "max-age=3600"), hours_ago(4))
+            .await
+            .unwrap();
+
+        let now = SystemTime::now();
+        assert!(
+            matches!(cache.load("/a", now).await, Some(Cached::Fresh(r)) if r.text == "a" && !r.stale)
+        );
+        assert!(matches!(cache.load("/b", now).await, Some(Cached::Stale(r)) if r.stale));
+        assert!(cache.load("/c", now).await.is_none());
+        assert!(!cache.path("/c").exists());
+        assert_eq!(
+            cache.stats(),
+            DiskCacheStats {
+                hits: 1,
+                stale_hits: 0,
+                misses: 2,
+                write_errors: 0,
+            }
+        );
+
+        cache.clear().await.unwrap();
+        assert!(cache.load("/a", now).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn entries_of_other_versions_are_ignored() {
+        let directory = temporary_directory("version");
+        let cache = DiskCache::new(&directory);
+        cache
+            .store("/a", &response("a", "max-age=3600"), SystemTime::now())
+            .await
+            .unwrap();
+
+        let path = cache.path("/a");
+        let contents = tokio::fs::read_to_string(&path).await.unwrap();
+        let outdated = contents.replacen(
+            &format!("\"version\":{}", CACHE_FORMAT_VERSION),
+            "\"version\":0",
+            1,
+        );
+        tokio::fs::write(&path, outdated).await.unwrap();
+
+        assert!(cache.load("/a", SystemTime::now()).await.is_none());
+        assert!(!path.exists());
+        assert!(cache
+            .directory()
+            .ends_with(format!("rust_of_clans-cache-v{}", CACHE_FORMAT_VERSION)));
+
+        cache.clear().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn directories_of_other_versions_are_deleted() {
+        let directory = temporary_directory("prune");
+        let outdated = directory.join("rust_of_clans-cache-v0");
+        let unrelated = directory.join("v1");
+        tokio::fs::create_dir_all(&outdated).await.unwrap();
+        tokio::fs::create_dir_all(&unrelated).await.unwrap();
+        tokio::fs::write(unrelated.join("release.txt"), "keep")
+            .await
+            .unwrap();
+
+        let cache = DiskCache::new(&directory);
+        cache
+            .store("/a", &response("a", "max-age=3600"), SystemTime::now())
+            .await
+            .unwrap();
+
+        assert!(!outdated.exists());
+        assert!(unrelated.join("release.txt").exists());
+        assert!(cache.path("/a").exists());
+
+        tokio::fs::remove_dir_all(&directory).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn write_errors_are_reported() {
+        let directory = temporary_directory("unwritable");
+        tokio::fs::write(&directory, "not a directory")
+            .await
+            .unwrap();
+
+        let cache = DiskCache::new(&directory);
+        let result = cache
+            .store("/a", &response("a", "max-age=3600"), SystemTime::now())
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(cache.stats().write_errors, 1);
+        assert!(cache
+            .last_write_error()
+            .is_some_and(|err| err.starts_with("cannot write")));
+
+        tokio::fs::remove_file(&directory).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn client_reuses_entries_across_restarts() {
+        let directory = temporary_directory("restart");
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(200, GOLD_PASS_BODY).header("Cache-Control", "public max-age=600")
+        })
+        .await;
+
+        for _ in 0..2 {
+            let coc_client = CoCClient::builder("MY_BEARER_TOKEN")
+                .base_url(server.url())
+                .disk_cache(DiskCache::new(&directory))
+                .build()
+                .unwrap();
+
+            coc_client.get_current_gold_pass().await.unwrap();
+        }
+
+        assert_eq!(server.requests().len(), 1);
+
+        DiskCache::new(&directory).clear().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn client_serves_stale_entry_during_maintenance() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                503,
+                r#"{"reason":"inMaintenance","message":"Down for maintenance"}"#,
+            )
+        })
+        .await;
+
+        let cache = DiskCache::new(temporary_directory("maintenance"));
+        let coc_client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .disk_cache(cache)
+            .build()
+            .unwrap();
+
+        let path = format!("{}/goldpass/seasons/current", coc_client.url());
+        let disk_cache = coc_client.disk_cache().unwrap();
+        disk_cache
+            .store(&path, &response(GOLD_PASS_BODY, "max-age=60"), hours_ago(1))
+            .await
+            .unwrap();
+
+        let client_response = coc_client
+            .send_get_request_with_query(&path, Vec::new())
+            .await
+            .unwrap();
+
+        assert!(client_response.stale);
+        assert_eq!(client_response.text, GOLD_PASS_BODY);
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(disk_cache.stats().stale_hits, 1);
+
+        disk_cache.clear().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn client_serves_stale_entry_when_unreachable() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let coc_client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(base_url)
+            .disk_cache(DiskCache::new(temporary_directory("unreachable")))
+            .build()
+            .unwrap();
+
+        let path = format!("{}/goldpass/seasons/current", coc_client.url());
+        let disk_cache = coc_client.disk_cache().unwrap();
+        disk_cache
+            .store(&path, &response(GOLD_PASS_BODY, "max-age=60"), hours_ago(1))
+            .await
+            .unwrap();
+
+        assert!(coc_client.get_current_gold_pass().await.is_ok());
+        assert_eq!(disk_cache.stats().stale_hits, 1);
+
+        disk_cache.clear().await.unwrap();
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderValue, AGE, CACHE_CONTROL};
+use tokio::time::Instant;
+
+use crate::client::ClientResponse;
+
+pub mod disk;
+
+/// Represents the hit and miss counters of a `ResponseCache`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of requests answered from the cache.
+    pub hits: u64,
+    /// The number of requests that had to be sent to the API.
+    pub misses: u64,
+    /// The number of entries dropped to make room for newer ones.
+    pub evictions: u64,
+    /// The number of entries currently held.
+    pub entries: usize,
+}
+
+#[derive(Debug)]
+struct Entry {
+    response: ClientResponse,
+    stored: Instant,
+    expires: Instant,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// Represents an in-memory cache of successful responses, keyed by path and query.
+///
+/// Responses are kept for as long as their `Cache-Control: max-age` allows. Once `capacity`
+/// entries are held, the least recently used entry is evicted.
+#[derive(Debug)]
+pub struct ResponseCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    /// Creates a cache holding up to `capacity` responses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Returns the maximum number of responses held.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the hit and miss counters of the cache.
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    /// Drops every cached response, keeping the counters.
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    /// Returns the fresh response cached under `key`, counting a hit or a miss.
+    pub(crate) fn get(&self, key: &str) -> Option<ClientResponse> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let now = Instant::now();
+
+        state.clock += 1;
+
+        match state.entries.get_mut(key) {
+            Some(entry) if entry.expires > now => {
+                entry.last_used = state.clock;
+                state.stats.hits += 1;
+
+                let mut response = entry.response.clone();
+                let age = now.duration_since(entry.stored).as_secs();
+                response.headers.insert(AGE, HeaderValue::from(age));
+                Some(response)
+            }
+            Some(_) => {
+                state.entries.remove(key);
+                state.stats.misses += 1;
+                None
+            }
+            None => {
+                state.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Caches `response` under `key` if it is successful and allows caching.
+    pub(crate) fn insert(&self, key: String, response: &ClientResponse) {
+        if !response.status_code.is_success() {
+            return;
+        }
+
+        let Some(max_age) = max_age(&response.headers) else {
+            return;
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        state.clock += 1;
+
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            state.entries.retain(|_, entry| entry.expires > now);
+        }
+
+        while !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+
+            state.entries.remove(&oldest);
+            state.stats.evictions += 1;
+        }
+
+        let last_used = state.clock;
+        state.entries.insert(
+            key,
+            Entry {
+                response: response.clone(),
+                stored: now,
+                expires: now + max_age,
+                last_used,
+            },
+        );
+    }
+}
+
+/// Returns the key identifying a request to `path` with `query`, independent of the parameter order.
+pub(crate) fn cache_key(path: &str, query: &[(String, String)]) -> String {
+    if query.is_empty() {
+        return path.to_string();
+    }
+
+    let mut pairs: Vec<String> = query
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{}={}",
+                urlencoding::encode(key),
+                urlencoding::encode(value)
+            )
+        })
+        .collect();
+    pairs.sort();
+
+    format!("{}?{}", path, pairs.join("&"))
+}
+
+/// Returns how long a response may be cached according to its `Cache-Control` header.
+///
+/// Returns `None` if the header is missing, forbids caching, or has a `max-age` of zero.
+pub(crate) fn max_age(headers: &HeaderMap) -> Option<Duration> {
+    let cache_control = headers.get(CACHE_CONTROL)?.to_str().ok()?;
+    let mut max_age = None;
+
+    // The API separates directives with spaces (`public max-age=120`), others with commas.
+    for directive in cache_control
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|directive| !directive.is_empty())
+    {
+        let directive = directive.to_ascii_lowercase();
+
+        if directive == "no-store" || directive == "no-cache" {
+            return None;
+        }
+
+        if let Some(seconds) = directive.strip_prefix("max-age=") {
+            max_age = seconds.trim_matches('"').parse::<u64>().ok();
+        }
+    }
+
+    max_age
+        .filter(|seconds| *seconds > 0)
+        .map(Duration::from_secs)
+}
+
+/// Returns when a response received at `received` expires, taking the `Age` added by caches into account.
+pub(crate) fn expires_at(headers: &HeaderMap, received: Instant) -> Option<Instant> {
+    let max_age = max_age(headers)?;
+    let age = headers
+        .get(AGE)
+        .and_then(|age| age.to_str().ok())
+        .and_then(|age| age.trim().parse::<u64>().ok())
+        .map_or(Duration::ZERO, Duration::from_secs);
+
+    Some(received + max_age.saturating_sub(age))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::client::CoCClient;
+    use crate::testing::{MockResponse, MockServer};
+
+    fn response(body: &str, cache_control: &str) -> ClientResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_str(cache_control).unwrap());
+
+        ClientResponse {
+            text: body.to_string(),
+            status_code: StatusCode::OK,
+            headers,
+            stale: false,
+            deserialization_mode: Default::default(),
+            drift: None,
+        }
+    }
+
+    #[test]
+    fn max_age_is_parsed() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(max_age(&headers), None);
+
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=120"),
+        );
+        assert_eq!(max_age(&headers), Some(Duration::from_secs(120)));
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=0"));
+        assert_eq!(max_age(&headers), None);
+
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("no-store, max-age=60"),
+        );
+        assert_eq!(max_age(&headers), None);
+    }
+
+    #[test]
+    fn cache_key_ignores_parameter_order() {
+        let first = vec![
+            (String::from("limit"), String::from("10")),
+            (String::from("name"), String::from("a b")),
+        ];
+        let second = vec![first[1].clone(), first[0].clone()];
+
+        assert_eq!(cache_key("/clans", &first), cache_key("/clans", &second));
+        assert_eq!(cache_key("/clans", &first), "/clans?limit=10&name=a%20b");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn entries_expire_after_max_age() {
+        let cache = ResponseCache::new(10);
+        cache.insert(String::from("/a"), &response("a", "max-age=60"));
+
+        assert!(cache.get("/a").is_some());
+
+        tokio::time::advance(Duration::from_secs(45)).await;
+
+        let cached = cache.get("/a").unwrap();
+        assert_eq!(cached.headers.get(AGE).unwrap(), "45");
+        assert_eq!(
+            expires_at(&cached.headers, Instant::now()),
+            Some(Instant::now() + Duration::from_secs(15))
+        );
+
+        tokio::time::advance(Duration::from_secs(16)).await;
+
+        assert!(cache.get("/a").is_none());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 2,
+                misses: 1,
+                evictions: 0,
+                entries: 0,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_entry_is_evicted() {
+        let cache = ResponseCache::new(2);
+        cache.insert(String::from("/a"), &response("a", "max-age=60"));
+        cache.insert(String::from("/b"), &response("b", "max-age=60"));
+
+        cache.get("/a");
+        cache.insert(String::from("/c"), &response("c", "max-age=60"));
+
+        assert!(cache.get("/a").is_some());
+        assert!(cache.get("/b").is_none());
+        assert!(cache.get("/c").is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[tokio::test]
+    async fn client_serves_fresh_responses_from_cache() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                200,
+                r#"{"startTime":"20230601T080000.000Z","endTime":"20230701T080000.000Z"}"#,
+            )
+            .header("Cache-Control", "public max-age=60")
+        })
+        .await;
+
+        let coc_client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .response_cache(100)
+            .build()
+            .unwrap();
+
+        for _ in 0..3 {
+            coc_client.get_current_gold_pass().await.unwrap();
+        }
+
+        assert_eq!(server.requests().len(), 1);
+
+        let stats = coc_client.response_cache().unwrap().stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 1, 1));
+    }
+}
@@ -2,38 +2,52 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::deserialization::api_enum;
+pub use crate::labels::models::Label;
+pub use crate::leagues::models::League;
+pub use crate::locations::models::Location;
+use crate::tag::Tag;
+use crate::timestamp::{self, Timestamp};
+
 /// Represents a Clash of Clans clan.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Clan {
     pub war_league: WarLeague,
     pub capital_league: CapitalLeague,
+    /// The members of the clan, empty in search results.
+    #[serde(default)]
     pub member_list: Vec<ClanMember>,
-    pub tag: String,
+    pub tag: Tag,
     pub clan_builder_base_points: i64,
-    pub clan_versus_points: i64,
     pub war_wins: i64,
-    pub war_ties: i64,
-    pub war_losses: i64,
+    /// The number of tied wars, only set if the war log is public.
+    pub war_ties: Option<i64>,
+    /// The number of lost wars, only set if the war log is public.
+    pub war_losses: Option<i64>,
     pub clan_points: i64,
+    #[serde(rename = "requiredTownhallLevel")]
     pub required_town_hall_level: Option<i64>,
-    pub chat_language: Language,
+    pub chat_language: Option<Language>,
     pub is_family_friendly: bool,
     pub clan_capital_points: i64,
     pub required_trophies: i64,
     pub required_builder_base_trophies: i64,
-    pub required_versus_trophies: i64,
     pub is_war_log_public: bool,
     pub war_frequency: WarFrequency,
     pub clan_level: i64,
     pub war_win_streak: i64,
     pub labels: Vec<Label>,
     pub name: String,
-    pub location: Location,
+    pub location: Option<Location>,
+    /// The description of the clan, empty in search results.
+    #[serde(default)]
     pub description: String,
+    pub badge_urls: HashMap<String, String>,
     pub members: i64,
     pub r#type: ClanType,
-    pub clan_capital: ClanCapital,
+    /// The clan capital, not set in search results.
+    pub clan_capital: Option<ClanCapital>,
 }
 
 /// Represents a member of a Clash of Clans clan.
@@ -42,10 +56,10 @@ pub struct Clan {
 pub struct ClanMember {
     pub league: League,
     pub builder_base_league: BuilderBaseLague,
-    pub versus_trophies: i64,
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
     pub role: ClanMemberRole,
+    pub town_hall_level: i64,
     pub exp_level: i64,
     pub clan_rank: i64,
     pub previous_clan_rank: i64,
@@ -56,15 +70,6 @@ pub struct ClanMember {
     pub player_house: Option<PlayerHouse>,
 }
 
-/// Represents a league in Clash of Clans.
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct League {
-    pub name: String,
-    pub id: i64,
-    pub icon_urls: HashMap<String, String>,
-}
-
 /// Represents the builder base league of a Clash of Clans player.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BuilderBaseLague {
@@ -99,45 +104,35 @@ pub struct PlayerHouseElement {
     pub r#type: PlayerHouseElementType,
 }
 
-/// Represents the type of the Clash of Clans clan.
-#[derive(Debug, Serialize, Deserialize)]
-pub enum ClanType {
-    #[serde(rename = "open")]
-    Open,
-    #[serde(rename = "inviteOnly")]
-    InviteOnly,
-    #[serde(rename = "closed")]
-    Closed,
-}
-
-/// Represents the role of a member in a Clash of Clans clan.
-#[derive(Debug, Serialize, Deserialize)]
-pub enum ClanMemberRole {
-    #[serde(rename = "notMember")]
-    NotMember,
-    #[serde(rename = "member")]
-    Member,
-    #[serde(rename = "leader")]
-    Leader,
-    #[serde(rename = "admin")]
-    Admin,
-    #[serde(rename = "coLeader")]
-    CoLeader,
-}
-
-/// Represents the type of an element in the player's house in Clash of Clans.
-#[derive(Debug, Serialize, Deserialize)]
-pub enum PlayerHouseElementType {
-    #[serde(rename = "ground")]
-    Ground,
-    #[serde(rename = "roof")]
-    Roof,
-    #[serde(rename = "foot")]
-    Foot,
-    #[serde(rename = "decoration")]
-    Decoration,
-    #[serde(rename = "walls")]
-    Walls,
+api_enum! {
+    /// Represents the type of the Clash of Clans clan.
+    pub enum ClanType {
+        Open => "open",
+        InviteOnly => "inviteOnly",
+        Closed => "closed",
+    }
+}
+
+api_enum! {
+    /// Represents the role of a member in a Clash of Clans clan.
+    pub enum ClanMemberRole {
+        NotMember => "notMember",
+        Member => "member",
+        Leader => "leader",
+        Admin => "admin",
+        CoLeader => "coLeader",
+    }
+}
+
+api_enum! {
+    /// Represents the type of an element in the player's house in Clash of Clans.
+    pub enum PlayerHouseElementType {
+        Ground => "ground",
+        Roof => "roof",
+        Foot => "foot",
+        Decoration => "decoration",
+        Walls => "walls",
+    }
 }
 
 /// Represents the language of a Clash of Clans chat.
@@ -149,32 +144,18 @@ pub struct Language {
     pub language_code: String,
 }
 
-/// Represents the war frequency of a Clash of Clans clan.
-#[derive(Serialize, Deserialize, Debug)]
-pub enum WarFrequency {
-    #[serde(rename = "unknown")]
-    Unknown,
-    #[serde(rename = "always")]
-    Always,
-    #[serde(rename = "moreThanOncePerWeek")]
-    MoreThanOncePerWeek,
-    #[serde(rename = "oncePerWeek")]
-    OncePerWeek,
-    #[serde(rename = "lessThanOncePerWeek")]
-    LessThanOncePerWeek,
-    #[serde(rename = "never")]
-    Never,
-    #[serde(rename = "any")]
-    Any,
-}
-
-/// Represents a label in a Clash of Clans clan.
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Label {
-    pub name: String,
-    pub id: i64,
-    pub badge_urls: Option<HashMap<String, String>>,
+api_enum! {
+    /// Represents the war frequency of a Clash of Clans clan.
+    pub enum WarFrequency {
+        /// The clan has not set a war frequency, sent by the API as `unknown`.
+        NotSet => "unknown",
+        Always => "always",
+        MoreThanOncePerWeek => "moreThanOncePerWeek",
+        OncePerWeek => "oncePerWeek",
+        LessThanOncePerWeek => "lessThanOncePerWeek",
+        Never => "never",
+        Any => "any",
+    }
 }
 
 /// Represents the clan capital in Clash of Clans.
@@ -194,44 +175,30 @@ pub struct ClanDistrictData {
     pub district_hall_level: i64,
 }
 
-/// Represents the location of a Clash of Clans clan or player.
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Location {
-    pub localized_name: Option<String>,
-    pub id: i64,
-    pub name: String,
-    pub is_country: bool,
-    pub country_code: String,
-}
-
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ClanWarLeagueGroup {
-    pub tag: Option<String>,
+    pub tag: Option<Tag>,
     pub state: ClanWarLeagueGroupState,
     pub season: Option<String>,
     pub clans: Option<Vec<ClanWarLeagueClan>>,
     pub rounds: Option<Vec<ClanWarLeagueRound>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ClanWarLeagueGroupState {
-    #[serde(rename = "groupNotFound")]
-    GroupNotFound,
-    #[serde(rename = "notInWar")]
-    NotInWar,
-    #[serde(rename = "preparation")]
-    Preparation,
-    #[serde(rename = "inWar")]
-    InWar,
-    #[serde(rename = "ended")]
-    Ended,
+api_enum! {
+    /// Represents the state of a clan war league group.
+    pub enum ClanWarLeagueGroupState {
+        GroupNotFound => "groupNotFound",
+        NotInWar => "notInWar",
+        Preparation => "preparation",
+        InWar => "inWar",
+        Ended => "ended",
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ClanWarLeagueClan {
-    pub tag: String,
+    pub tag: Tag,
     pub clan_level: i64,
     pub name: String,
     pub members: Vec<ClanWarLeagueClanMember>,
@@ -241,7 +208,7 @@ pub struct ClanWarLeagueClan {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ClanWarLeagueClanMember {
-    pub tag: String,
+    pub tag: Tag,
     pub town_hall_level: i64,
     pub name: String,
 }
@@ -249,7 +216,7 @@ pub struct ClanWarLeagueClanMember {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ClanWarLeagueRound {
-    pub war_tags: Vec<String>,
+    pub war_tags: Vec<Tag>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -257,9 +224,11 @@ pub struct ClanWarLeagueRound {
 pub struct ClanWarLogEntry {
     pub clan: WarClan,
     pub team_size: i64,
-    pub attacks_per_member: i64,
+    pub attacks_per_member: Option<i64>,
+    pub battle_modifier: Option<BattleModifier>,
     pub opponent: WarClan,
-    pub end_time: String,
+    #[serde(with = "timestamp::api_format")]
+    pub end_time: Timestamp,
     pub result: Option<ClanWarResult>,
 }
 
@@ -267,7 +236,7 @@ pub struct ClanWarLogEntry {
 #[serde(rename_all = "camelCase")]
 pub struct WarClan {
     pub destruction_percentage: f64,
-    pub tag: Option<String>,
+    pub tag: Option<Tag>,
     pub name: Option<String>,
     pub badge_urls: HashMap<String, String>,
     pub clan_level: i64,
@@ -280,7 +249,7 @@ pub struct WarClan {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ClanWarMember {
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
     pub map_position: i64,
     pub townhall_level: i64,
@@ -294,22 +263,21 @@ pub struct ClanWarMember {
 #[serde(rename_all = "camelCase")]
 pub struct ClanWarAttack {
     pub order: i64,
-    pub attacker_tag: String,
-    pub defender_tag: String,
+    pub attacker_tag: Tag,
+    pub defender_tag: Tag,
     pub cwc_score: Option<f32>,
     pub stars: i64,
     pub destruction_percentage: i64,
     pub duration: i64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum ClanWarResult {
-    #[serde(rename = "lose")]
-    Lose,
-    #[serde(rename = "win")]
-    NotInWar,
-    #[serde(rename = "tie")]
-    Preparation,
+api_enum! {
+    /// Represents the result of a clan war.
+    pub enum ClanWarResult {
+        Lose => "lose",
+        Win => "win",
+        Tie => "tie",
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -318,37 +286,51 @@ pub struct ClanWar {
     pub clan: WarClan,
     pub team_size: Option<i64>,
     pub attacks_per_member: Option<i64>,
+    pub battle_modifier: Option<BattleModifier>,
     pub opponent: WarClan,
-    pub start_time: Option<String>,
+    #[serde(default, with = "timestamp::option_api_format")]
+    pub start_time: Option<Timestamp>,
+    /// When the battle day of a clan war league war starts.
+    #[serde(default, with = "timestamp::option_api_format")]
+    pub war_start_time: Option<Timestamp>,
     pub state: ClanWarState,
-    pub end_time: Option<String>,
-    pub preparation_start_time: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ClanWarState {
-    #[serde(rename = "clanNotFound")]
-    ClanNotFound,
-    #[serde(rename = "accessDenied")]
-    AccessDenied,
-    #[serde(rename = "notInWar")]
-    NotInWar,
-    #[serde(rename = "inMatchmaking")]
-    InMatchMaking,
-    #[serde(rename = "enterWar")]
-    EnterWar,
-    #[serde(rename = "matched")]
-    Matched,
-    #[serde(rename = "preparation")]
-    Preparation,
-    #[serde(rename = "war")]
-    War,
-    #[serde(rename = "inWar")]
-    InWar,
-    #[serde(rename = "ended")]
-    Ended,
-    #[serde(rename = "warEnded")]
-    WarEnded,
+    #[serde(default, with = "timestamp::option_api_format")]
+    pub end_time: Option<Timestamp>,
+    #[serde(default, with = "timestamp::option_api_format")]
+    pub preparation_start_time: Option<Timestamp>,
+}
+
+api_enum! {
+    /// Represents the battle modifier of a clan war.
+    pub enum BattleModifier {
+        None => "none",
+        HardMode => "hardMode",
+    }
+}
+
+api_enum! {
+    /// Represents the state of a clan war.
+    pub enum ClanWarState {
+        ClanNotFound => "clanNotFound",
+        AccessDenied => "accessDenied",
+        NotInWar => "notInWar",
+        InMatchMaking => "inMatchmaking",
+        EnterWar => "enterWar",
+        Matched => "matched",
+        Preparation => "preparation",
+        War => "war",
+        InWar => "inWar",
+        Ended => "ended",
+        WarEnded => "warEnded",
+    }
+}
+
+api_enum! {
+    /// Represents the state of a clan capital raid season.
+    pub enum ClanCapitalRaidSeasonState {
+        Ongoing => "ongoing",
+        Ended => "ended",
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -356,9 +338,11 @@ pub enum ClanWarState {
 pub struct ClanCapitalRaidSeason {
     pub attack_log: Vec<ClanCapitalRaidSeasonAttackLogEntry>,
     pub defense_log: Vec<ClanCapitalRaidSeasonDefenseLogEntry>,
-    pub state: String,
-    pub start_rime: Option<String>,
-    pub end_time: String,
+    pub state: ClanCapitalRaidSeasonState,
+    #[serde(with = "timestamp::api_format")]
+    pub start_time: Timestamp,
+    #[serde(with = "timestamp::api_format")]
+    pub end_time: Timestamp,
     pub capital_total_loot: i64,
     pub raids_completed: i64,
     pub total_attacks: i64,
@@ -381,7 +365,7 @@ pub struct ClanCapitalRaidSeasonAttackLogEntry {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ClanCapitalRaidSeasonClanInfo {
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
     pub level: i64,
     pub badge_urls: HashMap<String, String>,
@@ -410,14 +394,14 @@ pub struct ClanCapitalRaidSeasonAttack {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ClanCapitalRaidSeasonAttacker {
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ClanCapitalRaidSeasonMember {
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
     pub attacks: i64,
     pub attack_limit: i64,
@@ -1,14 +1,19 @@
-use std::collections::HashMap;
-
-use urlencoding::encode;
+use futures::stream::{self, BoxStream, StreamExt};
 
 use crate::{
     client::{CoCClient, PagedResponse},
     errors::CoCClientError,
+    pagination::PaginationOptions,
+    params::{PageParams, QueryParams},
+    response::ApiResponse,
+    tag::IntoTag,
 };
 
-use super::models::{
-    Clan, ClanCapitalRaidSeason, ClanMember, ClanWar, ClanWarLeagueGroup, ClanWarLogEntry,
+use super::{
+    models::{
+        Clan, ClanCapitalRaidSeason, ClanMember, ClanWar, ClanWarLeagueGroup, ClanWarLogEntry,
+    },
+    search::ClanSearch,
 };
 
 impl CoCClient {
@@ -22,12 +27,15 @@ impl CoCClient {
     ///
     /// Returns a `Result` containing the clan information as `Clan` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
-    pub async fn get_clan_information(self: Self, clan_tag: &str) -> Result<Clan, CoCClientError> {
-        let encoded_clan_tag = encode(&clan_tag).into_owned();
+    pub async fn get_clan_information(
+        &self,
+        clan_tag: impl IntoTag,
+    ) -> Result<Clan, CoCClientError> {
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
 
-        let path = format!("{}/clans/{}", self.url, encoded_clan_tag);
+        let path = format!("{}/clans/{}", self.url(), encoded_clan_tag);
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -46,17 +54,18 @@ impl CoCClient {
     /// Returns a `Result` containing the current war league group as `ClanWarLeagueGroup` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_current_war_league_group(
-        self,
-        clan_tag: &str,
+        &self,
+        clan_tag: impl IntoTag,
     ) -> Result<ClanWarLeagueGroup, CoCClientError> {
-        let encoded_clan_tag = encode(&clan_tag).into_owned();
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
 
         let path = format!(
             "{}/clans/{}/currentwar/leaguegroup",
-            self.url, encoded_clan_tag
+            self.url(),
+            encoded_clan_tag
         );
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -75,14 +84,14 @@ impl CoCClient {
     /// Returns a `Result` containing the clan war league group information as `ClanWarLeagueGroup` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_clan_war_league_war(
-        self,
-        war_tag: &str,
+        &self,
+        war_tag: impl IntoTag,
     ) -> Result<ClanWar, CoCClientError> {
-        let encoded_war_tag = encode(&war_tag).into_owned();
+        let encoded_war_tag = war_tag.into_tag()?.encoded();
 
-        let path = format!("{}/clanwarleagues/wars/{}", self.url, encoded_war_tag);
+        let path = format!("{}/clanwarleagues/wars/{}", self.url(), encoded_war_tag);
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -95,22 +104,24 @@ impl CoCClient {
     /// # Arguments
     ///
     /// * `clan_tag` - The tag of the clan for which to retrieve the war log.
-    /// * `params` - Optional parameters for filtering the war log (e.g., limit, before, after).
+    /// * `params` - Optional paging parameters (limit, before, after).
     ///
     /// # Returns
     ///
     /// Returns a `Result` containing the clan war log as `ClanWarLog` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_clan_war_log(
-        self: Self,
-        clan_tag: &str,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        clan_tag: impl IntoTag,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<ClanWarLogEntry>, CoCClientError> {
-        let encoded_clan_tag = encode(&clan_tag).into_owned();
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
+
+        let path = format!("{}/clans/{}/warlog", self.url(), encoded_clan_tag);
 
-        let path = format!("{}/clans/{}/warlog", self.url, encoded_clan_tag);
+        let query = params.unwrap_or_default().to_query()?;
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -118,6 +129,27 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves clan information for the specified clan tag, together with the response metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `clan_tag` - The tag of the clan for which to retrieve information.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the clan information as `ApiResponse<Clan>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_clan_information_response(
+        &self,
+        clan_tag: impl IntoTag,
+    ) -> Result<ApiResponse<Clan>, CoCClientError> {
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
+
+        let path = format!("{}/clans/{}", self.url(), encoded_clan_tag);
+
+        self.get_api_response(&path, Vec::new()).await
+    }
+
     /// Retrieves information about the current clan war for the specified clan tag.
     ///
     /// # Arguments
@@ -129,14 +161,14 @@ impl CoCClient {
     /// Returns a `Result` containing the current clan war information as `ClanWar` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_current_clan_war(
-        self: Self,
-        clan_tag: &str,
+        &self,
+        clan_tag: impl IntoTag,
     ) -> Result<ClanWar, CoCClientError> {
-        let encoded_clan_tag = encode(&clan_tag).into_owned();
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
 
-        let path = format!("{}/clans/{}/currentwar", self.url, encoded_clan_tag);
+        let path = format!("{}/clans/{}/currentwar", self.url(), encoded_clan_tag);
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -144,27 +176,50 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves information about the current clan war, together with the response metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `clan_tag` - The tag of the clan for which to retrieve the current war information.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the current clan war information as `ApiResponse<ClanWar>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_current_clan_war_response(
+        &self,
+        clan_tag: impl IntoTag,
+    ) -> Result<ApiResponse<ClanWar>, CoCClientError> {
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
+
+        let path = format!("{}/clans/{}/currentwar", self.url(), encoded_clan_tag);
+
+        self.get_api_response(&path, Vec::new()).await
+    }
+
     /// Retrieves the members of the specified clan.
     ///
     /// # Arguments
     ///
     /// * `clan_tag` - The tag of the clan for which to retrieve the members.
-    /// * `params` - Optional parameters for filtering the clan members (e.g., limit, order).
+    /// * `params` - Optional paging parameters (limit, before, after).
     ///
     /// # Returns
     ///
     /// Returns a `Result` containing the clan members as `ClanMembers` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_clan_members(
-        self: Self,
-        clan_tag: &str,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        clan_tag: impl IntoTag,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<ClanMember>, CoCClientError> {
-        let encoded_clan_tag = encode(&clan_tag).into_owned();
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
 
-        let path = format!("{}/clans/{}/members", self.url, encoded_clan_tag);
+        let path = format!("{}/clans/{}/members", self.url(), encoded_clan_tag);
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -177,22 +232,28 @@ impl CoCClient {
     /// # Arguments
     ///
     /// * `clan_tag` - The tag of the clan for which to retrieve the capital raid seasons.
-    /// * `params` - Optional parameters for filtering the capital raid seasons (e.g., limit, start time).
+    /// * `params` - Optional paging parameters (limit, before, after).
     ///
     /// # Returns
     ///
     /// Returns a `Result` containing the capital raid seasons as `CapitalRaidSeasons` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_clan_capital_raid_seasons(
-        self: Self,
-        clan_tag: &str,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        clan_tag: impl IntoTag,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<ClanCapitalRaidSeason>, CoCClientError> {
-        let encoded_clan_tag = encode(&clan_tag).into_owned();
+        let encoded_clan_tag = clan_tag.into_tag()?.encoded();
+
+        let path = format!(
+            "{}/clans/{}/capitalraidseasons",
+            self.url(),
+            encoded_clan_tag
+        );
 
-        let path = format!("{}/clans/{}/capitalraidseasons", self.url, encoded_clan_tag);
+        let query = params.unwrap_or_default().to_query()?;
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -200,37 +261,151 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Searches all clans matching the specified filters.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The search filters and paging parameters.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the matching clans as `Clan` on success,
+    /// or a `CoCClientError` if the search is invalid or there was an error in the request or response.
     pub async fn get_clans(
-        self: Self,
-        params: HashMap<&str, &str>,
+        &self,
+        search: &ClanSearch,
     ) -> Result<PagedResponse<Clan>, CoCClientError> {
-        let path = format!("{}/clans", self.url);
+        let path = format!("{}/clans", self.url());
+
+        let query = search.to_query()?;
 
-        let client_response = match self.send_get_request(&path, Some(params)).await {
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
 
         CoCClient::handle_response(client_response).await
     }
+
+    /// Streams the whole clan war log for the specified clan tag, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `clan_tag` - The tag of the clan for which to retrieve the war log.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `ClanWarLogEntry`, or a `CoCClientError` if a page request fails.
+    pub fn stream_clan_war_log(
+        &self,
+        clan_tag: impl IntoTag,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<ClanWarLogEntry, CoCClientError>> {
+        let encoded_clan_tag = match clan_tag.into_tag() {
+            Ok(clan_tag) => clan_tag.encoded(),
+            Err(err) => return stream::once(async { Err(err) }).boxed(),
+        };
+
+        let path = format!("{}/clans/{}/warlog", self.url(), encoded_clan_tag);
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams all members of the specified clan, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `clan_tag` - The tag of the clan for which to retrieve the members.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `ClanMember`, or a `CoCClientError` if a page request fails.
+    pub fn stream_clan_members(
+        &self,
+        clan_tag: impl IntoTag,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<ClanMember, CoCClientError>> {
+        let encoded_clan_tag = match clan_tag.into_tag() {
+            Ok(clan_tag) => clan_tag.encoded(),
+            Err(err) => return stream::once(async { Err(err) }).boxed(),
+        };
+
+        let path = format!("{}/clans/{}/members", self.url(), encoded_clan_tag);
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams all capital raid seasons for the specified clan tag, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `clan_tag` - The tag of the clan for which to retrieve the capital raid seasons.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `ClanCapitalRaidSeason`, or a `CoCClientError` if a page request fails.
+    pub fn stream_clan_capital_raid_seasons(
+        &self,
+        clan_tag: impl IntoTag,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<ClanCapitalRaidSeason, CoCClientError>> {
+        let encoded_clan_tag = match clan_tag.into_tag() {
+            Ok(clan_tag) => clan_tag.encoded(),
+            Err(err) => return stream::once(async { Err(err) }).boxed(),
+        };
+
+        let path = format!(
+            "{}/clans/{}/capitalraidseasons",
+            self.url(),
+            encoded_clan_tag
+        );
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams every clan matching the search filters, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The search filters. Its paging parameters are replaced by `options`.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each matching `Clan`, or a `CoCClientError` if the search is
+    /// invalid or a page request fails.
+    pub fn stream_clans(
+        &self,
+        search: &ClanSearch,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<Clan, CoCClientError>> {
+        let path = format!("{}/clans", self.url());
+
+        match search.filter_query() {
+            Ok(query) => self.stream_items(path, query, options),
+            Err(err) => stream::once(async { Err(err) }).boxed(),
+        }
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
+    use crate::testing::MockServer;
 
     static CLAN_TAG: &str = "#2LUGVU89Q";
 
-    fn set_up_client() -> CoCClient {
-        let bearer_token = std::env::var("BEARER_TOKEN").expect("env var BEARER_TOKEN not set");
-
-        CoCClient::new(bearer_token, None)
+    async fn set_up_client() -> CoCClient {
+        MockServer::start().await.client().unwrap()
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_clan_information() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_clan_information(CLAN_TAG).await {
             Ok(_) => {
@@ -244,17 +419,16 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_current_war_league_group() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_current_war_league_group(CLAN_TAG).await {
             Ok(_) => {
                 assert!(true);
             }
             Err(err) => match err {
-                // Assert true on any client error, should just assert true on not found error.
-                CoCClientError::ClientError(_) => assert!(true),
+                // Assert true on a not found error, the clan is not taking part in a war league right now.
+                CoCClientError::NotFound(_) => assert!(true),
                 _ => {
                     println!("{}", err);
                     assert!(false)
@@ -264,9 +438,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_clan_war_league_war() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_clan_war_league_war(CLAN_TAG).await {
             Ok(_) => {
@@ -280,9 +453,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_clan_war_log() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_clan_war_log(CLAN_TAG, None).await {
             Ok(_) => {
@@ -296,9 +468,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_current_clan_war() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_current_clan_war(CLAN_TAG).await {
             Ok(_) => {
@@ -312,9 +483,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_clan_members() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_clan_members(CLAN_TAG, None).await {
             Ok(_) => {
@@ -328,13 +498,10 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_clan_members_with_limit_param() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
-        let mut params: HashMap<&str, &str> = HashMap::new();
-
-        params.insert("limit", "5");
+        let params = PageParams::new().limit(5);
 
         match client.get_clan_members(CLAN_TAG, Some(params)).await {
             Ok(_) => {
@@ -348,9 +515,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_clan_capital_raid_seasons() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_clan_capital_raid_seasons(CLAN_TAG, None).await {
             Ok(_) => {
@@ -364,15 +530,28 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
-    async fn test_get_clans() {
-        let client = set_up_client();
+    async fn test_stream_clan_members() {
+        let client = set_up_client().await;
+
+        let members: Vec<_> = client
+            .stream_clan_members(CLAN_TAG, PaginationOptions::new().page_size(5))
+            .collect()
+            .await;
 
-        let mut params: HashMap<&str, &str> = HashMap::new();
+        for member in members {
+            if let Err(err) = member {
+                panic!("{}", err);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_clans() {
+        let client = set_up_client().await;
 
-        params.insert("name", "erlendgemmer");
+        let search = ClanSearch::new().name("erlendgemmer");
 
-        match client.get_clans(params).await {
+        match client.get_clans(&search).await {
             Ok(_) => {
                 assert!(true);
             }
@@ -0,0 +1,270 @@
+use crate::{
+    errors::CoCClientError,
+    labels::models::ClanLabelId,
+    params::{PageParams, QueryParams},
+};
+
+use super::models::{Label, Location, WarFrequency};
+
+/// The smallest and largest member counts accepted by the `minMembers` and `maxMembers` filters.
+const MEMBER_BOUNDS: std::ops::RangeInclusive<u32> = 2..=50;
+
+/// The minimum number of characters accepted by the `name` filter.
+const MIN_NAME_LENGTH: usize = 3;
+
+/// Represents a clan search against the `/clans` endpoint.
+///
+/// The API's rules are checked before the request is sent: at least one filter must be set,
+/// `name` must be at least 3 characters long, and the member bounds must lie within `2..=50`.
+#[derive(Clone, Debug, Default)]
+pub struct ClanSearch {
+    /// Search clans by name, at least 3 characters.
+    pub name: Option<String>,
+    /// Filter by clan war frequency.
+    pub war_frequency: Option<WarFrequency>,
+    /// Filter by clan location identifier.
+    pub location_id: Option<i64>,
+    /// Filter by minimum number of clan members.
+    pub min_members: Option<u32>,
+    /// Filter by maximum number of clan members.
+    pub max_members: Option<u32>,
+    /// Filter by minimum amount of clan points.
+    pub min_clan_points: Option<u32>,
+    /// Filter by minimum clan level.
+    pub min_clan_level: Option<u32>,
+    /// Filter by clan labels, all of which must be present on the clan.
+    pub label_ids: Vec<i64>,
+    /// The paging parameters of the search.
+    pub page: PageParams,
+}
+
+impl ClanSearch {
+    /// Creates an empty search. At least one filter has to be set before it is sent.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Searches clans by name.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Filters clans by war frequency.
+    pub fn war_frequency(mut self, war_frequency: WarFrequency) -> Self {
+        self.war_frequency = Some(war_frequency);
+        self
+    }
+
+    /// Filters clans by location.
+    pub fn location(self, location: &Location) -> Self {
+        self.location_id(location.id)
+    }
+
+    /// Filters clans by location identifier.
+    pub fn location_id(mut self, location_id: i64) -> Self {
+        self.location_id = Some(location_id);
+        self
+    }
+
+    /// Filters clans by minimum number of members.
+    pub fn min_members(mut self, min_members: u32) -> Self {
+        self.min_members = Some(min_members);
+        self
+    }
+
+    /// Filters clans by maximum number of members.
+    pub fn max_members(mut self, max_members: u32) -> Self {
+        self.max_members = Some(max_members);
+        self
+    }
+
+    /// Filters clans by minimum amount of clan points.
+    pub fn min_clan_points(mut self, min_clan_points: u32) -> Self {
+        self.min_clan_points = Some(min_clan_points);
+        self
+    }
+
+    /// Filters clans by minimum clan level.
+    pub fn min_clan_level(mut self, min_clan_level: u32) -> Self {
+        self.min_clan_level = Some(min_clan_level);
+        self
+    }
+
+    /// Requires the clans to have the given label.
+    pub fn label(self, label: &Label) -> Self {
+        self.label_id(label.id)
+    }
+
+    /// Requires the clans to have the given known label.
+    pub fn clan_label(self, label: ClanLabelId) -> Self {
+        self.label_id(label.id())
+    }
+
+    /// Requires the clans to have the label with the given identifier.
+    pub fn label_id(mut self, label_id: i64) -> Self {
+        self.label_ids.push(label_id);
+        self
+    }
+
+    /// Sets the paging parameters of the search.
+    pub fn page(mut self, page: PageParams) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// Validates the filters and serializes them into query pairs, without the paging parameters.
+    pub(crate) fn filter_query(&self) -> Result<Vec<(String, String)>, CoCClientError> {
+        let mut query = Vec::new();
+
+        if let Some(name) = &self.name {
+            if name.trim().chars().count() < MIN_NAME_LENGTH {
+                return Err(CoCClientError::InvalidParameters(format!(
+                    "`name` must be at least {} characters long, got {:?}",
+                    MIN_NAME_LENGTH, name
+                )));
+            }
+            query.push((String::from("name"), name.clone()));
+        }
+
+        if let Some(war_frequency) = &self.war_frequency {
+            query.push((
+                String::from("warFrequency"),
+                war_frequency.as_str().to_string(),
+            ));
+        }
+
+        if let Some(location_id) = self.location_id {
+            query.push((String::from("locationId"), location_id.to_string()));
+        }
+
+        for (key, members) in [
+            ("minMembers", self.min_members),
+            ("maxMembers", self.max_members),
+        ] {
+            if let Some(members) = members {
+                if !MEMBER_BOUNDS.contains(&members) {
+                    return Err(CoCClientError::InvalidParameters(format!(
+                        "`{}` must be between {} and {}, got {}",
+                        key,
+                        MEMBER_BOUNDS.start(),
+                        MEMBER_BOUNDS.end(),
+                        members
+                    )));
+                }
+                query.push((String::from(key), members.to_string()));
+            }
+        }
+
+        if let (Some(min_members), Some(max_members)) = (self.min_members, self.max_members) {
+            if min_members > max_members {
+                return Err(CoCClientError::InvalidParameters(format!(
+                    "`minMembers` ({}) cannot be greater than `maxMembers` ({})",
+                    min_members, max_members
+                )));
+            }
+        }
+
+        if let Some(min_clan_points) = self.min_clan_points {
+            query.push((String::from("minClanPoints"), min_clan_points.to_string()));
+        }
+
+        if let Some(min_clan_level) = self.min_clan_level {
+            query.push((String::from("minClanLevel"), min_clan_level.to_string()));
+        }
+
+        if !self.label_ids.is_empty() {
+            let label_ids: Vec<String> = self.label_ids.iter().map(|id| id.to_string()).collect();
+            query.push((String::from("labelIds"), label_ids.join(",")));
+        }
+
+        if query.is_empty() {
+            return Err(CoCClientError::InvalidParameters(String::from(
+                "at least one search filter must be set",
+            )));
+        }
+
+        Ok(query)
+    }
+}
+
+impl QueryParams for ClanSearch {
+    fn to_query(&self) -> Result<Vec<(String, String)>, CoCClientError> {
+        let mut query = self.filter_query()?;
+        query.extend(self.page.to_query()?);
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (String::from(key), String::from(value))
+    }
+
+    #[test]
+    fn clan_search_to_query() {
+        let search = ClanSearch::new()
+            .name("rust of clans")
+            .war_frequency(WarFrequency::Always)
+            .location_id(32000006)
+            .min_members(10)
+            .max_members(50)
+            .min_clan_points(1000)
+            .min_clan_level(5)
+            .label_id(56000000)
+            .label_id(56000001)
+            .page(PageParams::new().limit(10));
+
+        assert_eq!(
+            search.to_query().unwrap(),
+            vec![
+                pair("name", "rust of clans"),
+                pair("warFrequency", "always"),
+                pair("locationId", "32000006"),
+                pair("minMembers", "10"),
+                pair("maxMembers", "50"),
+                pair("minClanPoints", "1000"),
+                pair("minClanLevel", "5"),
+                pair("labelIds", "56000000,56000001"),
+                pair("limit", "10"),
+            ]
+        );
+    }
+
+    #[test]
+    fn clan_search_requires_a_filter() {
+        let search = ClanSearch::new().page(PageParams::new().limit(10));
+
+        assert!(matches!(
+            search.to_query(),
+            Err(CoCClientError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn clan_search_rejects_short_name() {
+        let search = ClanSearch::new().name("ab");
+
+        assert!(matches!(
+            search.to_query(),
+            Err(CoCClientError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn clan_search_rejects_member_bounds() {
+        for search in [
+            ClanSearch::new().min_members(1),
+            ClanSearch::new().max_members(51),
+            ClanSearch::new().min_members(20).max_members(10),
+        ] {
+            assert!(matches!(
+                search.to_query(),
+                Err(CoCClientError::InvalidParameters(_))
+            ));
+        }
+    }
+}
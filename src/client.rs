@@ -1,23 +1,66 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
 
-use reqwest::{header, Client, StatusCode};
+use reqwest::{
+    header::{HeaderMap, CONTENT_TYPE},
+    Client, Method, StatusCode,
+};
 use serde::{Deserialize, Serialize};
-
-use crate::errors::{ClientError, CoCClientError, ServerError};
+use tokio::time::Instant;
+
+use crate::builder::CoCClientBuilder;
+use crate::cache::{
+    self,
+    disk::{Cached, DiskCache},
+    ResponseCache,
+};
+use crate::deserialization::{self, DeserializationMode};
+use crate::drift::{self, SchemaDriftDetector};
+use crate::errors::CoCClientError;
+use crate::params::QueryParams;
+use crate::rate_limit::RateLimiter;
+use crate::retry::{RetryEvent, RetryPolicy, RetryableErrorKind};
+use crate::tokens::TokenPool;
+use crate::vcr::{Cassette, CassetteMode, CassetteRequest, CassetteResponse, Interaction};
 
 #[derive(Clone, Debug)]
 /// Represents a Clash of Clans API client.
+///
+/// The client is cheap to clone: all clones share the same connection pool and configuration,
+/// so it can be handed to as many tasks as needed.
 pub struct CoCClient {
-    pub base_url: String,
-    pub bearer_token: String,
-    pub client: Option<Client>,
-    pub url: String,
-    pub version: String,
+    inner: Arc<ClientInner>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ClientInner {
+    pub(crate) base_url: String,
+    pub(crate) tokens: TokenPool,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) retry_policy: Option<RetryPolicy>,
+    pub(crate) cache: Option<ResponseCache>,
+    pub(crate) disk_cache: Option<DiskCache>,
+    pub(crate) cassette: Option<Cassette>,
+    pub(crate) keep_raw_body: bool,
+    pub(crate) deserialization_mode: DeserializationMode,
+    pub(crate) drift_detector: Option<SchemaDriftDetector>,
+    pub(crate) client: Client,
+    pub(crate) url: String,
+    pub(crate) version: String,
 }
 
+#[derive(Clone, Debug)]
 pub struct ClientResponse {
     pub text: String,
     pub status_code: StatusCode,
+    pub headers: HeaderMap,
+    /// Whether the response is an expired cache entry served because the API was unavailable.
+    pub stale: bool,
+    /// How the body is deserialized by `handle_response`.
+    pub deserialization_mode: DeserializationMode,
+    /// The detector `handle_response` reports to, with the endpoint the response came from.
+    pub(crate) drift: Option<(SchemaDriftDetector, String)>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -37,6 +80,15 @@ pub struct PagedResponse<T> {
     pub paging: Option<Paging>,
 }
 
+impl<T> PagedResponse<T> {
+    /// Returns the cursor pointing at the next page, if there is one.
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.paging
+            .as_ref()
+            .and_then(|paging| paging.cursors.after.as_deref())
+    }
+}
+
 impl CoCClient {
     /// Creates a new instance of `CoCClient`.
     ///
@@ -44,42 +96,108 @@ impl CoCClient {
     ///
     /// * `bearer_token` - A string representing the bearer token used for authentication.
     /// * `client` - An optional `Client` instance for making HTTP requests. If `None` is provided,
-    ///              a default `Client` will be created with custom headers.
+    ///   a default `Client` will be created.
     ///
     /// # Returns
     ///
     /// A new instance of `CoCClient` initialized with the provided bearer token and client.
+    /// Use `CoCClient::builder` to configure the base URL, timeouts or other settings.
     ///
     pub fn new(bearer_token: String, client: Option<Client>) -> Self {
-        let base_url = String::from("https://api.clashofclans.com");
+        let mut builder = CoCClientBuilder::new(bearer_token);
 
-        let version = String::from("v1");
+        if let Some(client) = client {
+            builder = builder.http_client(client);
+        }
 
-        let url = format!("{}/{}", base_url, version);
+        builder
+            .build()
+            .expect("the default client configuration is valid")
+    }
 
-        let client: Client = match client {
-            Some(client) => client,
-            None => {
-                let mut headers = header::HeaderMap::new();
+    /// Creates a `CoCClientBuilder` for configuring a client with the given bearer token.
+    pub fn builder(bearer_token: impl Into<String>) -> CoCClientBuilder {
+        CoCClientBuilder::new(bearer_token)
+    }
 
-                let mut auth_value =
-                    header::HeaderValue::from_str(&format!("Bearer {}", bearer_token)).unwrap();
+    pub(crate) fn from_inner(inner: ClientInner) -> Self {
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
 
-                auth_value.set_sensitive(true);
+    /// Returns the base URL of the API, e.g. `https://api.clashofclans.com`.
+    pub fn base_url(&self) -> &str {
+        &self.inner.base_url
+    }
 
-                headers.insert(header::AUTHORIZATION, auth_value);
+    /// Returns the bearer token used for authentication, or the first key's token when a pool is used.
+    pub fn bearer_token(&self) -> &str {
+        self.inner
+            .tokens
+            .keys()
+            .first()
+            .map_or("", |key| key.token.as_str())
+    }
 
-                Client::builder().default_headers(headers).build().unwrap()
-            }
-        };
+    /// Returns the pool of API keys the requests are distributed across, with their usage counters.
+    pub fn token_pool(&self) -> &TokenPool {
+        &self.inner.tokens
+    }
 
-        Self {
-            base_url,
-            bearer_token,
-            client: Some(client),
-            url,
-            version,
-        }
+    /// Returns the rate limiter of the client, if one is configured.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.inner.rate_limiter.as_ref()
+    }
+
+    /// Returns the retry policy of the client, if one is configured.
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.inner.retry_policy.as_ref()
+    }
+
+    /// Returns the response cache, if one was configured.
+    pub fn response_cache(&self) -> Option<&ResponseCache> {
+        self.inner.cache.as_ref()
+    }
+
+    /// Returns the on-disk response cache, if one was configured.
+    pub fn disk_cache(&self) -> Option<&DiskCache> {
+        self.inner.disk_cache.as_ref()
+    }
+
+    /// Returns the cassette requests are recorded into or replayed from, if one was configured.
+    pub fn cassette(&self) -> Option<&Cassette> {
+        self.inner.cassette.as_ref()
+    }
+
+    /// Returns whether the raw body is kept in every `ApiResponse`.
+    pub fn keeps_raw_body(&self) -> bool {
+        self.inner.keep_raw_body
+    }
+
+    /// Returns how responses that do not match the models are deserialized.
+    pub fn deserialization_mode(&self) -> DeserializationMode {
+        self.inner.deserialization_mode
+    }
+
+    /// Returns the schema drift detector, if drift detection was enabled.
+    pub fn schema_drift_detector(&self) -> Option<&SchemaDriftDetector> {
+        self.inner.drift_detector.as_ref()
+    }
+
+    /// Returns the URL every endpoint path is appended to, e.g. `https://api.clashofclans.com/v1`.
+    pub fn url(&self) -> &str {
+        &self.inner.url
+    }
+
+    /// Returns the version of the API, e.g. `v1`.
+    pub fn version(&self) -> &str {
+        &self.inner.version
+    }
+
+    /// Returns the underlying HTTP client.
+    pub fn http_client(&self) -> &Client {
+        &self.inner.client
     }
 
     /// Handles the response from the client and deserializes it into the specified type `T`.
@@ -96,27 +214,32 @@ impl CoCClient {
     where
         T: serde::de::DeserializeOwned,
     {
-        match client_response.status_code {
-            status_code if status_code == StatusCode::OK => {
-                let result: T = serde_json::from_str(&client_response.text)
-                    .map_err(CoCClientError::Deserlisation)?;
-                Ok(result)
-            }
-            StatusCode::BAD_REQUEST
-            | StatusCode::FORBIDDEN
-            | StatusCode::NOT_FOUND
-            | StatusCode::TOO_MANY_REQUESTS => {
-                let error_body: ClientError = serde_json::from_str(&client_response.text)
-                    .map_err(CoCClientError::Deserlisation)?;
-                Err(CoCClientError::ClientError(error_body))
-            }
-            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
-                let error_body: ServerError = serde_json::from_str(&client_response.text)
-                    .map_err(CoCClientError::Deserlisation)?;
-                Err(CoCClientError::ServerError(error_body))
-            }
-            _ => Err(CoCClientError::UnkownError),
+        Self::deserialize_response(client_response).map(|(data, _)| data)
+    }
+
+    /// Deserializes a response like `handle_response`, also returning the fields filled in lenient mode.
+    pub(crate) fn deserialize_response<T>(
+        client_response: ClientResponse,
+    ) -> Result<(T, Vec<String>), CoCClientError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        if client_response.status_code != StatusCode::OK {
+            return Err(CoCClientError::from_response(
+                client_response.status_code,
+                &client_response.text,
+            ));
+        }
+
+        if let Some((drift_detector, endpoint)) = &client_response.drift {
+            drift_detector.observe::<T>(endpoint, &client_response.text);
         }
+
+        deserialization::from_str_with_filled_fields(
+            &client_response.text,
+            client_response.deserialization_mode,
+        )
+        .map_err(CoCClientError::Deserlisation)
     }
 
     /// Sends a GET request to the specified path and returns the client response.
@@ -124,35 +247,298 @@ impl CoCClient {
     /// # Arguments
     ///
     /// * `path` - The path to which the GET request should be sent.
+    /// * `params` - Optional raw query parameters, sent without validation.
     ///
     /// # Returns
     ///
     /// Returns a `Result` containing the client response on success, or a `CoCClientError`
     /// if there was an error sending the request or receiving the response.
+    #[deprecated(
+        since = "0.8.0",
+        note = "use `send_get_request_with_query` with a typed `QueryParams` value instead"
+    )]
     pub async fn send_get_request(
-        self,
+        &self,
         path: &str,
         params: Option<HashMap<&str, &str>>,
     ) -> Result<ClientResponse, CoCClientError> {
-        let client = self.client.ok_or(CoCClientError::MissingClientError)?;
+        let query = match params {
+            Some(params) => params.to_query()?,
+            None => Vec::new(),
+        };
 
-        let mut request_builder = client.get(path);
+        self.send_get_request_with_query(path, query).await
+    }
 
-        if let Some(params) = params {
-            for (key, value) in params {
-                request_builder = request_builder.query(&[(key, value)]);
+    /// Sends a GET request to the specified path with the given query pairs and returns the client response.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to which the GET request should be sent.
+    /// * `query` - The query pairs, usually produced by `QueryParams::to_query`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the client response on success, or a `CoCClientError`
+    /// if there was an error sending the request or receiving the response.
+    /// Fresh responses are served from the configured caches, and expired on-disk entries
+    /// are served flagged as `stale` if the API is in maintenance or cannot be reached.
+    pub async fn send_get_request_with_query(
+        &self,
+        path: &str,
+        query: Vec<(String, String)>,
+    ) -> Result<ClientResponse, CoCClientError> {
+        self.send_cached(path, query)
+            .await
+            .map(|client_response| self.prepare_response(path, client_response))
+    }
+
+    /// Sends a GET request through the configured caches.
+    async fn send_cached(
+        &self,
+        path: &str,
+        query: Vec<(String, String)>,
+    ) -> Result<ClientResponse, CoCClientError> {
+        let response_cache = self.inner.cache.as_ref();
+        let disk_cache = self.inner.disk_cache.as_ref();
+
+        if response_cache.is_none() && disk_cache.is_none() {
+            return self
+                .send_with_retries(&Method::GET, path, &query, None)
+                .await;
+        }
+
+        let key = cache::cache_key(path, &query);
+
+        if let Some(client_response) = response_cache.and_then(|cache| cache.get(&key)) {
+            return Ok(client_response);
+        }
+
+        let stale = match disk_cache {
+            Some(disk_cache) => match disk_cache.load(&key, SystemTime::now()).await {
+                Some(Cached::Fresh(client_response)) => return Ok(client_response),
+                Some(Cached::Stale(client_response)) => Some(client_response),
+                None => None,
+            },
+            None => None,
+        };
+
+        let result = self
+            .send_with_retries(&Method::GET, path, &query, None)
+            .await;
+
+        let unavailable = match &result {
+            Ok(client_response) => client_response.status_code == StatusCode::SERVICE_UNAVAILABLE,
+            Err(err) => matches!(err, CoCClientError::Request(_)),
+        };
+
+        if let (true, Some(disk_cache), Some(client_response)) = (unavailable, disk_cache, stale) {
+            disk_cache.record_stale_hit();
+            return Ok(client_response);
+        }
+
+        if let Ok(client_response) = &result {
+            if let Some(response_cache) = response_cache {
+                response_cache.insert(key.clone(), client_response);
+            }
+
+            if let Some(disk_cache) = disk_cache {
+                // The response is served regardless, and the error is kept in `DiskCache::stats`.
+                let _ = disk_cache
+                    .store(&key, client_response, SystemTime::now())
+                    .await;
             }
         }
 
-        let response = request_builder
-            .send()
+        result
+    }
+
+    /// Sends a POST request with a JSON body to the specified path and returns the client response.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to which the POST request should be sent.
+    /// * `body` - The value serialized as the JSON body of the request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the client response on success, or a `CoCClientError`
+    /// if the body cannot be serialized (`CoCClientError::Serialization`), or there was an error sending
+    /// the request or receiving the response.
+    /// POST responses are never cached.
+    pub async fn send_post_request<B>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<ClientResponse, CoCClientError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let body = serde_json::to_string(body).map_err(CoCClientError::Serialization)?;
+
+        self.send_request(Method::POST, path, Vec::new(), Some(body))
             .await
-            .map_err(CoCClientError::Request)?;
+    }
+
+    /// Sends a request with the given method, query pairs and optional JSON body, and returns the client response.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method of the request.
+    /// * `path` - The path to which the request should be sent.
+    /// * `query` - The query pairs, usually produced by `QueryParams::to_query`.
+    /// * `body` - The optional JSON body, sent with `Content-Type: application/json`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the client response on success, or a `CoCClientError`
+    /// if there was an error sending the request or receiving the response.
+    /// GET requests without a body go through the configured caches, other requests bypass them.
+    pub async fn send_request(
+        &self,
+        method: Method,
+        path: &str,
+        query: Vec<(String, String)>,
+        body: Option<String>,
+    ) -> Result<ClientResponse, CoCClientError> {
+        if method == Method::GET && body.is_none() {
+            return self.send_get_request_with_query(path, query).await;
+        }
+
+        self.send_with_retries(&method, path, &query, body.as_deref())
+            .await
+            .map(|client_response| self.prepare_response(path, client_response))
+    }
+
+    /// Sets how `handle_response` deserializes a response and reports its drift.
+    fn prepare_response(&self, path: &str, mut client_response: ClientResponse) -> ClientResponse {
+        client_response.deserialization_mode = self.inner.deserialization_mode;
+        client_response.drift = self.inner.drift_detector.clone().map(|drift_detector| {
+            let path = path.strip_prefix(self.url()).unwrap_or(path);
+            (drift_detector, drift::endpoint_template(path))
+        });
+        client_response
+    }
+
+    /// Sends a request, retrying failures according to the retry policy.
+    async fn send_with_retries(
+        &self,
+        method: &Method,
+        path: &str,
+        query: &[(String, String)],
+        body: Option<&str>,
+    ) -> Result<ClientResponse, CoCClientError> {
+        let Some(retry_policy) = &self.inner.retry_policy else {
+            return self.send_once(method, path, query, body).await;
+        };
+
+        let started = Instant::now();
+        let mut attempt = 1;
+
+        loop {
+            let result = self.send_once(method, path, query, body).await;
+
+            let Some(kind) = RetryableErrorKind::classify(&result) else {
+                return result;
+            };
+
+            let Some(delay) = retry_policy.next_delay(attempt, kind, started.elapsed()) else {
+                return result;
+            };
+
+            retry_policy.notify(&RetryEvent {
+                path: path.to_string(),
+                attempt,
+                kind,
+                status_code: result
+                    .ok()
+                    .map(|client_response| client_response.status_code),
+                delay,
+            });
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends a single request with a key from the token pool, without retrying.
+    async fn send_once(
+        &self,
+        method: &Method,
+        path: &str,
+        query: &[(String, String)],
+        body: Option<&str>,
+    ) -> Result<ClientResponse, CoCClientError> {
+        let cassette = self.inner.cassette.as_ref().map(|cassette| {
+            let path = path.strip_prefix(self.url()).unwrap_or(path);
+            (cassette, CassetteRequest::new(method, path, query, body))
+        });
+
+        if let Some((cassette, request)) = &cassette {
+            if cassette.mode() == CassetteMode::Replay {
+                return cassette.replay_request(request).await;
+            }
+        }
+
+        let key = self.inner.tokens.acquire()?;
+
+        if let Some(rate_limiter) = &self.inner.rate_limiter {
+            rate_limiter.acquire(key).await;
+        }
+
+        let mut request = self
+            .inner
+            .client
+            .request(method.clone(), path)
+            .bearer_auth(self.inner.tokens.token(key))
+            .query(query);
+
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        }
+
+        let response = request.send().await.map_err(CoCClientError::Request)?;
 
         let status_code = response.status();
+        let headers = response.headers().clone();
         let text = response.text().await.map_err(CoCClientError::Request)?;
 
-        let client_response = ClientResponse { text, status_code };
+        self.inner.tokens.report(key, status_code, &text);
+
+        if let Some(rate_limiter) = &self.inner.rate_limiter {
+            if status_code == StatusCode::TOO_MANY_REQUESTS {
+                rate_limiter.throttled(key);
+            } else if status_code.is_success() {
+                rate_limiter.succeeded(key);
+            }
+        }
+
+        if let Some((cassette, request)) = cassette {
+            let interaction = Interaction {
+                request,
+                response: CassetteResponse::new(status_code, &headers, &text),
+            };
+            let tokens: Vec<&str> = self
+                .inner
+                .tokens
+                .keys()
+                .iter()
+                .map(|key| key.token.as_str())
+                .collect();
+
+            cassette.record_interaction(interaction, &tokens).await?;
+        }
+
+        let client_response = ClientResponse {
+            text,
+            status_code,
+            headers,
+            stale: false,
+            deserialization_mode: self.inner.deserialization_mode,
+            drift: None,
+        };
 
         Ok(client_response)
     }
@@ -174,14 +560,32 @@ mod tests {
 
         let coc_client = CoCClient::new(bearer_token, None);
 
-        assert_eq!(coc_client.bearer_token, String::from("MY_BEARER_TOKEN"));
+        assert_eq!(coc_client.bearer_token(), "MY_BEARER_TOKEN");
+
+        assert_eq!(coc_client.base_url(), base_url);
 
-        assert_eq!(coc_client.base_url, base_url);
+        assert_eq!(coc_client.version(), version);
 
-        assert_eq!(coc_client.version, version);
+        assert_eq!(coc_client.url(), url);
+    }
 
-        assert_eq!(coc_client.url, url);
+    #[tokio::test]
+    async fn post_bodies_that_cannot_be_serialized_are_rejected() {
+        let coc_client = CoCClient::new(String::from("MY_BEARER_TOKEN"), None);
+        let body = std::collections::HashMap::from([((1, 2), 3)]);
+
+        let err = coc_client
+            .send_post_request("http://127.0.0.1:9/v1/items", &body)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, CoCClientError::Serialization(_)), "{}", err);
+    }
+
+    #[test]
+    fn coc_client_is_shareable() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
 
-        assert!(coc_client.client.is_some())
+        assert_send_sync::<CoCClient>();
     }
 }
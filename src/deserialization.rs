@@ -0,0 +1,437 @@
+use std::cell::Cell;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use serde_path_to_error::Segment;
+
+/// Controls how responses that do not match the models are deserialized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DeserializationMode {
+    /// Unknown enum values become `Unknown(..)`, so a new value added by a game update does not
+    /// break responses. Missing required fields fail with `CoCClientError::Deserlisation`.
+    #[default]
+    Standard,
+    /// Unknown enum values become `Unknown(..)`, and missing required fields are filled with
+    /// empty values (`0`, `""`, `false`, `[]` or `{}`).
+    ///
+    /// The filled fields are listed in `ApiResponse::filled_fields`, and in the missing fields of
+    /// the schema drift report.
+    Lenient,
+    /// Unknown enum values and missing required fields fail with `CoCClientError::Deserlisation`,
+    /// e.g. to notice API changes in CI.
+    Strict,
+}
+
+/// The values tried, in order, for a missing required field in lenient mode.
+const FILLERS: [fn() -> Value; 5] = [
+    || Value::from(0),
+    || Value::from(""),
+    || Value::from(false),
+    || Value::Array(Vec::new()),
+    || Value::Object(Default::default()),
+];
+
+/// The maximum number of missing fields filled in a single response.
+const MAX_FILLED_FIELDS: usize = 64;
+
+thread_local! {
+    /// The mode of the current thread, `DeserializationMode::default()` outside of `from_str`.
+    static MODE: Cell<DeserializationMode> = Cell::new(DeserializationMode::default());
+}
+
+/// Returns whether the current thread is deserializing in strict mode.
+pub(crate) fn is_strict() -> bool {
+    MODE.with(Cell::get) == DeserializationMode::Strict
+}
+
+/// Sets the mode of the current thread until dropped.
+struct ModeGuard {
+    previous: DeserializationMode,
+}
+
+impl ModeGuard {
+    fn enter(mode: DeserializationMode) -> Self {
+        ModeGuard {
+            previous: MODE.with(|current| current.replace(mode)),
+        }
+    }
+}
+
+impl Drop for ModeGuard {
+    fn drop(&mut self) {
+        MODE.with(|current| current.set(self.previous));
+    }
+}
+
+/// Deserializes a response body according to the given mode.
+///
+/// # Arguments
+///
+/// * `text` - The JSON body of the response.
+/// * `mode` - Whether unknown enum values and missing fields are tolerated.
+///
+/// # Returns
+///
+/// Returns the deserialized value on success, or the `serde_json::Error` of the first mismatch
+/// that could not be tolerated.
+pub fn from_str<T>(text: &str, mode: DeserializationMode) -> Result<T, serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    from_str_with_filled_fields(text, mode).map(|(data, _)| data)
+}
+
+/// Deserializes a response body like `from_str`, also returning the fields filled in lenient mode.
+///
+/// # Arguments
+///
+/// * `text` - The JSON body of the response.
+/// * `mode` - Whether unknown enum values and missing fields are tolerated.
+///
+/// # Returns
+///
+/// Returns the deserialized value with the paths of the filled fields, in the format of the
+/// schema drift report, e.g. `items[].name`. The paths are empty if nothing was filled.
+pub fn from_str_with_filled_fields<T>(
+    text: &str,
+    mode: DeserializationMode,
+) -> Result<(T, Vec<String>), serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    let _guard = ModeGuard::enter(mode);
+
+    let err = match serde_json::from_str(text) {
+        Ok(data) => return Ok((data, Vec::new())),
+        Err(err) => err,
+    };
+
+    if mode != DeserializationMode::Lenient || missing_field(&err.to_string()).is_none() {
+        return Err(err);
+    }
+
+    let mut value: Value = serde_json::from_str(text)?;
+
+    let (data, missing) = fill_missing_fields(&mut value);
+    let data = data.ok_or(err)?;
+
+    let mut filled: Vec<String> = Vec::new();
+    for field in missing {
+        if !filled.contains(&field.path) {
+            filled.push(field.path);
+        }
+    }
+
+    Ok((data, filled))
+}
+
+/// Represents a required field missing from a response.
+pub(crate) struct MissingField {
+    /// The path of the field, e.g. `items[].name`.
+    pub(crate) path: String,
+    /// The object the field is missing from, as received.
+    pub(crate) parent: Value,
+}
+
+/// Deserializes `value`, filling missing required fields until it matches `T`.
+///
+/// Fields that are optional or have a default are never reported, since `T` does not need them.
+///
+/// # Returns
+///
+/// Returns the deserialized value, or `None` if a mismatch other than a missing field remains,
+/// together with every missing field found, once per occurrence.
+pub(crate) fn fill_missing_fields<T>(value: &mut Value) -> (Option<T>, Vec<MissingField>)
+where
+    T: DeserializeOwned,
+{
+    // The path of the field filled last, and the index of the filler it holds.
+    let mut filling: Option<(Vec<Segment>, usize)> = None;
+    let mut missing = Vec::new();
+    let mut original = value.clone();
+
+    for _ in 0..MAX_FILLED_FIELDS * FILLERS.len() {
+        let err = match serde_path_to_error::deserialize::<_, T>(&*value) {
+            Ok(data) => return (Some(data), missing),
+            Err(err) => err,
+        };
+
+        let path: Vec<Segment> = err.path().iter().cloned().collect();
+        let message = err.into_inner().to_string();
+
+        let (field_path, filler) = match (missing_field(&message), filling.take()) {
+            (Some(field), _) => {
+                let mut field_path = path;
+                field_path.push(Segment::Map {
+                    key: field.to_string(),
+                });
+                (field_path, 0)
+            }
+            (None, Some((field_path, filler)))
+                if same_path(&field_path, &path) && filler + 1 < FILLERS.len() =>
+            {
+                (field_path, filler + 1)
+            }
+            _ => return (None, missing),
+        };
+
+        let Some((Segment::Map { key }, parent_path)) = field_path.split_last() else {
+            return (None, missing);
+        };
+        let Some(parent) = lookup(value, parent_path).and_then(Value::as_object_mut) else {
+            return (None, missing);
+        };
+
+        parent.insert(key.clone(), FILLERS[filler]());
+
+        if filler == 0 {
+            missing.push(MissingField {
+                path: display(&field_path),
+                parent: lookup(&mut original, parent_path)
+                    .cloned()
+                    .unwrap_or_default(),
+            });
+        }
+
+        filling = Some((field_path, filler));
+    }
+
+    (None, missing)
+}
+
+/// Returns the name of the field if `message` reports a missing field.
+fn missing_field(message: &str) -> Option<&str> {
+    message.strip_prefix("missing field `")?.split('`').next()
+}
+
+/// Returns the value at `path`.
+fn lookup<'a>(value: &'a mut Value, path: &[Segment]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        Segment::Map { key } => value.get_mut(key),
+        Segment::Seq { index } => value.get_mut(index),
+        Segment::Enum { .. } | Segment::Unknown => Some(value),
+    })
+}
+
+/// Formats `path` like the schema drift report, e.g. `items[].name`.
+fn display(path: &[Segment]) -> String {
+    path.iter()
+        .fold(String::new(), |display, segment| match segment {
+            Segment::Map { key } if display.is_empty() => key.clone(),
+            Segment::Map { key } => format!("{}.{}", display, key),
+            Segment::Seq { .. } => format!("{}[]", display),
+            Segment::Enum { .. } | Segment::Unknown => display,
+        })
+}
+
+fn same_path(left: &[Segment], right: &[Segment]) -> bool {
+    left.len() == right.len()
+        && left.iter().zip(right).all(|pair| match pair {
+            (Segment::Map { key: left }, Segment::Map { key: right }) => left == right,
+            (Segment::Seq { index: left }, Segment::Seq { index: right }) => left == right,
+            _ => false,
+        })
+}
+
+/// Defines an enum of the API's string values with an `Unknown(String)` fallback.
+///
+/// Unknown values deserialize into `Unknown`, except in strict mode where they are rejected.
+macro_rules! api_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $value:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            /// A value added to the API after this version of the crate.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// Returns the value as sent by the API.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+
+            /// Returns whether the value is unknown to this version of the crate.
+            pub fn is_unknown(&self) -> bool {
+                matches!(self, $name::Unknown(_))
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    value => $name::Unknown(value.to_string()),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+                let parsed = $name::from(value.as_str());
+
+                if parsed.is_unknown() && $crate::deserialization::is_strict() {
+                    return Err(serde::de::Error::unknown_variant(&value, &[$($value),*]));
+                }
+
+                Ok(parsed)
+            }
+        }
+    };
+}
+
+pub(crate) use api_enum;
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::tag::Tag;
+
+    api_enum! {
+        pub enum Weather {
+            Sunny => "sunny",
+            Rainy => "rainy",
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Forecast {
+        city: String,
+        weather: Weather,
+        temperature: i64,
+        days: Vec<Day>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Day {
+        index: i64,
+        windy: bool,
+        station: Station,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Station {
+        name: String,
+    }
+
+    #[test]
+    fn unknown_enum_values_fall_back_in_lenient_mode() {
+        let weather: Weather = from_str(r#""foggy""#, DeserializationMode::Lenient).unwrap();
+
+        assert_eq!(weather, Weather::Unknown(String::from("foggy")));
+        assert_eq!(weather.to_string(), "foggy");
+        assert_eq!(serde_json::to_string(&weather).unwrap(), r#""foggy""#);
+        assert_eq!(Weather::from("rainy"), Weather::Rainy);
+    }
+
+    #[test]
+    fn unknown_enum_values_fail_in_strict_mode() {
+        let err = from_str::<Weather>(r#""foggy""#, DeserializationMode::Strict).unwrap_err();
+
+        assert!(
+            err.to_string().contains("unknown variant `foggy`"),
+            "{}",
+            err
+        );
+        assert!(!is_strict());
+    }
+
+    #[test]
+    fn missing_fields_are_filled_in_lenient_mode() {
+        let json =
+            r#"{"weather":"sunny","days":[{"index":1,"station":{"name":"A"}},{"windy":true}]}"#;
+
+        let (forecast, filled): (Forecast, _) =
+            from_str_with_filled_fields(json, DeserializationMode::Lenient).unwrap();
+
+        assert_eq!(
+            filled,
+            vec![
+                "days[].windy",
+                "days[].index",
+                "days[].station",
+                "days[].station.name",
+                "city",
+                "temperature"
+            ]
+        );
+        assert_eq!(forecast.city, "");
+        assert_eq!(forecast.temperature, 0);
+        assert!(!forecast.days[0].windy);
+        assert_eq!(forecast.days[1].index, 0);
+        assert_eq!(forecast.days[1].station.name, "");
+    }
+
+    #[test]
+    fn missing_fields_fail_in_strict_mode() {
+        let json = r#"{"weather":"sunny","temperature":20,"days":[]}"#;
+
+        let err = from_str::<Forecast>(json, DeserializationMode::Strict).unwrap_err();
+
+        assert!(err.to_string().contains("missing field `city`"), "{}", err);
+    }
+
+    #[test]
+    fn default_mode_accepts_unknown_values_but_not_missing_fields() {
+        let json = r#"{"city":"Oslo","weather":"foggy","temperature":3,"days":[]}"#;
+
+        let forecast: Forecast = from_str(json, DeserializationMode::default()).unwrap();
+        let err = from_str::<Forecast>(r#"{"weather":"foggy"}"#, DeserializationMode::default())
+            .unwrap_err();
+        let weather: Weather = serde_json::from_str(r#""foggy""#).unwrap();
+
+        assert_eq!(forecast.weather, Weather::Unknown(String::from("foggy")));
+        assert!(err.to_string().contains("missing field `city`"), "{}", err);
+        assert_eq!(weather, Weather::Unknown(String::from("foggy")));
+    }
+
+    #[test]
+    fn unfillable_fields_keep_the_original_error() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Member {
+            tag: Tag,
+        }
+
+        let err = from_str::<Member>("{}", DeserializationMode::Lenient).unwrap_err();
+
+        assert!(err.to_string().contains("missing field `tag`"), "{}", err);
+    }
+}
@@ -0,0 +1,501 @@
+use std::fmt;
+use std::net::IpAddr;
+
+use reqwest::{header, Client, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    builder::CoCClientBuilder,
+    errors::CoCClientError,
+    tokens::{ApiKey, TokenPool},
+};
+
+use super::models::{
+    CreateKeyRequest, KeyListResponse, KeyResponse, LoginRequest, LoginResponse, PortalKey,
+    PortalStatus, RevokeKeyRequest,
+};
+
+/// The URL of the official Clash of Clans developer portal.
+pub const DEFAULT_PORTAL_URL: &str = "https://developer.clashofclans.com";
+
+/// The URL of the service answering with the public IP address of the caller, as plain text.
+pub const DEFAULT_IP_LOOKUP_URL: &str = "https://api.ipify.org";
+
+/// The name given to the keys created by the key manager, unless configured otherwise.
+const DEFAULT_KEY_NAME: &str = "rust_of_clans";
+
+/// The maximum number of keys a developer account can hold.
+const MAX_KEYS: usize = 10;
+
+/// Manages the API keys of a developer account, so the client always has a key for the current IP.
+///
+/// Keys are bound to source IPs, so the manager logs into the developer portal, looks up the
+/// current public IP, and reuses or creates a key for it. Stale keys with the same name that are
+/// bound to other IPs are revoked, so every host should use its own key name.
+///
+/// By default the public IP is looked up with the third-party service at `DEFAULT_IP_LOOKUP_URL`
+/// (`https://api.ipify.org`), which receives a request from the host every time a key is ensured.
+/// Use `ip_lookup_url` to point the lookup at a service of your own instead.
+#[derive(Clone)]
+pub struct KeyManager {
+    email: String,
+    password: String,
+    portal_url: String,
+    ip_lookup_url: String,
+    key_name: String,
+    key_description: String,
+    http_client: Client,
+}
+
+impl KeyManager {
+    /// Creates a key manager for the developer account with the given credentials.
+    pub fn new(email: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            email: email.into(),
+            password: password.into(),
+            portal_url: String::from(DEFAULT_PORTAL_URL),
+            ip_lookup_url: String::from(DEFAULT_IP_LOOKUP_URL),
+            key_name: String::from(DEFAULT_KEY_NAME),
+            key_description: String::from("Created by rust_of_clans"),
+            http_client: Client::new(),
+        }
+    }
+
+    /// Sets the base URL of the developer portal, e.g. a local stand-in server.
+    pub fn portal_url(mut self, portal_url: impl Into<String>) -> Self {
+        self.portal_url = portal_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the URL used to look up the public IP address, which must answer with the IP as plain text.
+    pub fn ip_lookup_url(mut self, ip_lookup_url: impl Into<String>) -> Self {
+        self.ip_lookup_url = ip_lookup_url.into();
+        self
+    }
+
+    /// Sets the name of the keys created and reused by the manager.
+    pub fn key_name(mut self, key_name: impl Into<String>) -> Self {
+        self.key_name = key_name.into();
+        self
+    }
+
+    /// Sets the description of the keys created by the manager.
+    pub fn key_description(mut self, key_description: impl Into<String>) -> Self {
+        self.key_description = key_description.into();
+        self
+    }
+
+    /// Uses a prebuilt `Client` for the requests to the portal and the IP lookup.
+    pub fn http_client(mut self, http_client: Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Looks up the public IP address requests are currently sent from.
+    ///
+    /// # Returns
+    ///
+    /// Returns the public IP address on success, or a `CoCClientError` if the lookup failed
+    /// or did not answer with an IP address.
+    pub async fn current_ip(&self) -> Result<IpAddr, CoCClientError> {
+        let response = self
+            .http_client
+            .get(&self.ip_lookup_url)
+            .send()
+            .await
+            .map_err(CoCClientError::Request)?;
+
+        let text = response.text().await.map_err(CoCClientError::Request)?;
+
+        text.trim().parse().map_err(|_| {
+            CoCClientError::DeveloperPortal(format!(
+                "IP lookup did not answer with an IP address: {:?}",
+                text.trim()
+            ))
+        })
+    }
+
+    /// Logs into the developer portal.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `PortalSession` for managing the keys of the account on success,
+    /// or a `CoCClientError` if the credentials were rejected or the request failed.
+    pub async fn login(&self) -> Result<PortalSession<'_>, CoCClientError> {
+        let response = self
+            .http_client
+            .post(format!("{}/api/login", self.portal_url))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(
+                serde_json::to_string(&LoginRequest {
+                    email: self.email.clone(),
+                    password: self.password.clone(),
+                })
+                .map_err(CoCClientError::Deserlisation)?,
+            )
+            .send()
+            .await
+            .map_err(CoCClientError::Request)?;
+
+        let cookie = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .collect::<Vec<&str>>()
+            .join("; ");
+
+        let status_code = response.status();
+        let text = response.text().await.map_err(CoCClientError::Request)?;
+        let _: LoginResponse = parse_portal_response(status_code, &text)?;
+
+        Ok(PortalSession {
+            manager: self,
+            cookie,
+        })
+    }
+
+    /// Returns a key usable from the current public IP, creating one if needed.
+    ///
+    /// An existing key with the configured name bound to the current IP is reused. Otherwise the
+    /// keys with the configured name bound to other IPs are revoked and a new key is created.
+    ///
+    /// # Returns
+    ///
+    /// Returns the key with its IP binding on success, or a `CoCClientError` if the account
+    /// has no room for another key or a request failed.
+    pub async fn ensure_key(&self) -> Result<ApiKey, CoCClientError> {
+        let ip = self.current_ip().await?;
+        let session = self.login().await?;
+        let keys = session.list_keys().await?;
+
+        if let Some(key) = keys
+            .iter()
+            .find(|key| key.name == self.key_name && key.allows_ip(ip))
+        {
+            return Ok(ApiKey::new(key.key.clone()).allowed_ips([ip]));
+        }
+
+        let stale: Vec<&PortalKey> = keys
+            .iter()
+            .filter(|key| key.name == self.key_name)
+            .collect();
+
+        for key in &stale {
+            session.revoke_key(&key.id).await?;
+        }
+
+        if keys.len() - stale.len() >= MAX_KEYS {
+            return Err(CoCClientError::DeveloperPortal(format!(
+                "the developer account already has {} keys, revoke one to create a key for {}",
+                MAX_KEYS, ip
+            )));
+        }
+
+        let key = session.create_key(ip).await?;
+
+        Ok(ApiKey::new(key.key).allowed_ips([ip]))
+    }
+
+    /// Creates a `CoCClientBuilder` using a key for the current public IP.
+    ///
+    /// # Returns
+    ///
+    /// Returns a builder whose token pool holds the key and knows the current egress IP,
+    /// or a `CoCClientError` if no key could be obtained.
+    pub async fn client_builder(&self) -> Result<CoCClientBuilder, CoCClientError> {
+        let key = self.ensure_key().await?;
+        let egress_ip = key.allowed_ips.first().copied();
+
+        let token_pool = TokenPool::new([key]);
+        token_pool.set_egress_ip(egress_ip);
+
+        Ok(CoCClientBuilder::with_token_pool(token_pool))
+    }
+}
+
+impl fmt::Debug for KeyManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyManager")
+            .field("email", &self.email)
+            .field("password", &"<redacted>")
+            .field("portal_url", &self.portal_url)
+            .field("ip_lookup_url", &self.ip_lookup_url)
+            .field("key_name", &self.key_name)
+            .finish()
+    }
+}
+
+/// Represents a logged-in session on the developer portal.
+#[derive(Debug)]
+pub struct PortalSession<'a> {
+    manager: &'a KeyManager,
+    cookie: String,
+}
+
+impl PortalSession<'_> {
+    /// Lists the API keys of the developer account.
+    pub async fn list_keys(&self) -> Result<Vec<PortalKey>, CoCClientError> {
+        let response: KeyListResponse = self
+            .post("/api/apikey/list", &serde_json::json!({}))
+            .await?;
+
+        Ok(response.keys)
+    }
+
+    /// Creates an API key bound to `ip`, using the manager's key name and description.
+    pub async fn create_key(&self, ip: IpAddr) -> Result<PortalKey, CoCClientError> {
+        let request = CreateKeyRequest {
+            name: self.manager.key_name.clone(),
+            description: self.manager.key_description.clone(),
+            cidr_ranges: vec![ip.to_string()],
+            scopes: vec![String::from("clash")],
+        };
+
+        let response: KeyResponse = self.post("/api/apikey/create", &request).await?;
+
+        response.key.ok_or_else(|| {
+            CoCClientError::DeveloperPortal(String::from("the portal did not return the new key"))
+        })
+    }
+
+    /// Revokes the API key with the given id.
+    pub async fn revoke_key(&self, id: &str) -> Result<(), CoCClientError> {
+        let request = RevokeKeyRequest { id: id.to_string() };
+
+        let _: KeyResponse = self.post("/api/apikey/revoke", &request).await?;
+
+        Ok(())
+    }
+
+    async fn post<B, T>(&self, path: &str, body: &B) -> Result<T, CoCClientError>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        let response = self
+            .manager
+            .http_client
+            .post(format!("{}{}", self.manager.portal_url, path))
+            .header(header::COOKIE, &self.cookie)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(body).map_err(CoCClientError::Deserlisation)?)
+            .send()
+            .await
+            .map_err(CoCClientError::Request)?;
+
+        let status_code = response.status();
+        let text = response.text().await.map_err(CoCClientError::Request)?;
+
+        parse_portal_response(status_code, &text)
+    }
+}
+
+fn parse_portal_response<T>(status_code: StatusCode, text: &str) -> Result<T, CoCClientError>
+where
+    T: DeserializeOwned,
+{
+    if status_code == StatusCode::OK {
+        return serde_json::from_str(text).map_err(CoCClientError::Deserlisation);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ErrorBody {
+        status: Option<PortalStatus>,
+        description: Option<String>,
+    }
+
+    let message = serde_json::from_str::<ErrorBody>(text)
+        .ok()
+        .and_then(|body| {
+            body.status
+                .map(|status| status.message)
+                .or(body.description)
+        })
+        .unwrap_or_else(|| text.to_string());
+
+    Err(CoCClientError::DeveloperPortal(format!(
+        "{} ({})",
+        message, status_code
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::testing::{MockResponse, MockServer, RecordedRequest};
+
+    const OK_STATUS: &str = r#"{"code":0,"message":"ok","detail":null}"#;
+
+    fn key_json(id: &str, name: &str, ip: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": name,
+            "description": "Created by rust_of_clans",
+            "cidrRanges": [ip],
+            "scopes": ["clash"],
+            "key": format!("token-{}", id),
+        })
+    }
+
+    /// Starts a stand-in portal holding `keys`, answering the IP lookup with `203.0.113.7`.
+    async fn set_up_portal(keys: Vec<serde_json::Value>) -> MockServer {
+        let keys = Arc::new(Mutex::new(keys));
+
+        MockServer::start_with_handler(move |request: &RecordedRequest| {
+            let status: serde_json::Value = serde_json::from_str(OK_STATUS).unwrap();
+            let logged_in = request.header("cookie") == Some("session=abc");
+
+            match request.path.as_str() {
+                "/ip" => MockResponse::text(200, "203.0.113.7\n"),
+                "/api/login" => {
+                    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+                    if body["password"] == "secret" {
+                        MockResponse::json(
+                            200,
+                            serde_json::json!({"status": status, "developer": {"id": "dev"}})
+                                .to_string(),
+                        )
+                        .header("Set-Cookie", "session=abc; Path=/; HttpOnly")
+                    } else {
+                        MockResponse::json(
+                            403,
+                            r#"{"status":{"code":403,"message":"Invalid credentials"}}"#,
+                        )
+                    }
+                }
+                _ if !logged_in => MockResponse::json(403, r#"{"description":"Forbidden"}"#),
+                "/api/apikey/list" => MockResponse::json(
+                    200,
+                    serde_json::json!({"status": status, "keys": *keys.lock().unwrap()})
+                        .to_string(),
+                ),
+                "/api/apikey/revoke" => {
+                    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+                    keys.lock().unwrap().retain(|key| key["id"] != body["id"]);
+                    MockResponse::json(200, serde_json::json!({"status": status}).to_string())
+                }
+                "/api/apikey/create" => {
+                    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+                    let key = key_json(
+                        "new",
+                        body["name"].as_str().unwrap(),
+                        body["cidrRanges"][0].as_str().unwrap(),
+                    );
+                    keys.lock().unwrap().push(key.clone());
+                    MockResponse::json(
+                        200,
+                        serde_json::json!({"status": status, "key": key}).to_string(),
+                    )
+                }
+                _ => MockResponse::json(404, "{}"),
+            }
+        })
+        .await
+    }
+
+    fn set_up_manager(server: &MockServer, password: &str) -> KeyManager {
+        KeyManager::new("dev@example.com", password)
+            .portal_url(server.url())
+            .ip_lookup_url(format!("{}/ip", server.url()))
+    }
+
+    fn paths(server: &MockServer) -> Vec<String> {
+        server
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn ensure_key_reuses_key_for_current_ip() {
+        let server = set_up_portal(vec![key_json("1", "rust_of_clans", "203.0.113.7/32")]).await;
+        let manager = set_up_manager(&server, "secret");
+
+        let key = manager.ensure_key().await.unwrap();
+
+        assert_eq!(key.token, "token-1");
+        assert_eq!(
+            key.allowed_ips,
+            vec!["203.0.113.7".parse::<IpAddr>().unwrap()]
+        );
+        assert_eq!(
+            paths(&server),
+            vec!["/ip", "/api/login", "/api/apikey/list"]
+        );
+    }
+
+    #[tokio::test]
+    async fn ensure_key_revokes_stale_keys_and_creates_key() {
+        let server = set_up_portal(vec![
+            key_json("1", "rust_of_clans", "198.51.100.1"),
+            key_json("2", "other-host", "198.51.100.2"),
+        ])
+        .await;
+        let manager = set_up_manager(&server, "secret");
+
+        let key = manager.ensure_key().await.unwrap();
+
+        assert_eq!(key.token, "token-new");
+        assert_eq!(
+            paths(&server),
+            vec![
+                "/ip",
+                "/api/login",
+                "/api/apikey/list",
+                "/api/apikey/revoke",
+                "/api/apikey/create",
+            ]
+        );
+
+        let create = server.requests().pop().unwrap();
+        let body: serde_json::Value = serde_json::from_str(&create.body).unwrap();
+        assert_eq!(body["cidrRanges"], serde_json::json!(["203.0.113.7"]));
+    }
+
+    #[tokio::test]
+    async fn ensure_key_fails_when_account_is_full() {
+        let keys = (0..MAX_KEYS)
+            .map(|id| key_json(&id.to_string(), "other-host", "198.51.100.1"))
+            .collect();
+        let server = set_up_portal(keys).await;
+        let manager = set_up_manager(&server, "secret");
+
+        let result = manager.ensure_key().await;
+
+        assert!(matches!(result, Err(CoCClientError::DeveloperPortal(_))));
+    }
+
+    #[tokio::test]
+    async fn login_rejects_invalid_credentials() {
+        let server = set_up_portal(Vec::new()).await;
+        let manager = set_up_manager(&server, "wrong");
+
+        let result = manager.login().await;
+
+        match result {
+            Err(CoCClientError::DeveloperPortal(message)) => {
+                assert!(message.contains("Invalid credentials"), "{}", message)
+            }
+            _ => panic!("expected a developer portal error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_builder_uses_managed_key() {
+        let server = set_up_portal(vec![key_json("1", "rust_of_clans", "203.0.113.7")]).await;
+        let manager = set_up_manager(&server, "secret");
+
+        let coc_client = manager.client_builder().await.unwrap().build().unwrap();
+
+        assert_eq!(coc_client.bearer_token(), "token-1");
+        assert_eq!(
+            coc_client.token_pool().egress_ip(),
+            Some("203.0.113.7".parse().unwrap())
+        );
+    }
+}
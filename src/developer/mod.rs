@@ -0,0 +1,2 @@
+pub mod key_manager;
+pub mod models;
@@ -0,0 +1,154 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+/// Represents the status returned with every developer portal response.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PortalStatus {
+    pub code: i64,
+    pub message: String,
+    pub detail: Option<String>,
+}
+
+/// Represents the body of a login request to the developer portal.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+/// Represents the response to a successful login to the developer portal.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResponse {
+    pub status: PortalStatus,
+    pub developer: Option<Developer>,
+}
+
+/// Represents the developer account logged into the developer portal.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Developer {
+    pub id: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Represents an API key as listed by the developer portal.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortalKey {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub cidr_ranges: Vec<String>,
+    pub scopes: Option<Vec<String>>,
+    pub key: String,
+}
+
+impl PortalKey {
+    /// Returns whether the key can be used from `ip`, i.e. whether one of its CIDR ranges contains it.
+    ///
+    /// Ranges without a prefix length, e.g. `203.0.113.7`, only contain that address.
+    pub fn allows_ip(&self, ip: IpAddr) -> bool {
+        self.cidr_ranges
+            .iter()
+            .any(|range| cidr_contains(range, ip))
+    }
+}
+
+/// Returns whether the CIDR range `range` contains `ip`. Malformed ranges contain nothing.
+fn cidr_contains(range: &str, ip: IpAddr) -> bool {
+    let (address, prefix_length) = match range.trim().split_once('/') {
+        Some((address, prefix_length)) => (address, prefix_length.parse::<u32>().ok()),
+        None => (range.trim(), None),
+    };
+
+    let Ok(address) = address.parse::<IpAddr>() else {
+        return false;
+    };
+
+    let (network, ip, bits) = match (address, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => (
+            u128::from(u32::from(network)),
+            u128::from(u32::from(ip)),
+            32,
+        ),
+        (IpAddr::V6(network), IpAddr::V6(ip)) => (u128::from(network), u128::from(ip), 128),
+        _ => return false,
+    };
+
+    let prefix_length = match prefix_length {
+        Some(prefix_length) if prefix_length <= bits => prefix_length,
+        Some(_) => return false,
+        None => bits,
+    };
+
+    let host_bits = bits - prefix_length;
+    network.checked_shr(host_bits).unwrap_or(0) == ip.checked_shr(host_bits).unwrap_or(0)
+}
+
+/// Represents the response listing the API keys of the developer account.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeyListResponse {
+    pub status: PortalStatus,
+    pub keys: Vec<PortalKey>,
+}
+
+/// Represents the response to creating or revoking an API key.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeyResponse {
+    pub status: PortalStatus,
+    pub key: Option<PortalKey>,
+}
+
+/// Represents the body of a request creating an API key.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateKeyRequest {
+    pub name: String,
+    pub description: String,
+    pub cidr_ranges: Vec<String>,
+    pub scopes: Vec<String>,
+}
+
+/// Represents the body of a request revoking an API key.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokeKeyRequest {
+    pub id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(cidr_ranges: &[&str]) -> PortalKey {
+        PortalKey {
+            id: String::from("1"),
+            name: String::from("rust_of_clans"),
+            description: None,
+            cidr_ranges: cidr_ranges.iter().map(|range| range.to_string()).collect(),
+            scopes: None,
+            key: String::from("token"),
+        }
+    }
+
+    #[test]
+    fn allows_ip_checks_cidr_ranges() {
+        let key = key(&["203.0.113.0/24", "198.51.100.7", "2001:db8::/32", "bogus/8"]);
+
+        assert!(key.allows_ip("203.0.113.4".parse().unwrap()));
+        assert!(!key.allows_ip("203.0.114.4".parse().unwrap()));
+        assert!(key.allows_ip("198.51.100.7".parse().unwrap()));
+        assert!(!key.allows_ip("198.51.100.8".parse().unwrap()));
+        assert!(key.allows_ip("2001:db8::1".parse().unwrap()));
+        assert!(!key.allows_ip("2001:db9::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn allows_ip_handles_edge_prefixes() {
+        assert!(key(&["0.0.0.0/0"]).allows_ip("192.0.2.1".parse().unwrap()));
+        assert!(key(&["192.0.2.1/32"]).allows_ip("192.0.2.1".parse().unwrap()));
+        assert!(!key(&["192.0.2.1/33"]).allows_ip("192.0.2.1".parse().unwrap()));
+    }
+}
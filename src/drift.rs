@@ -0,0 +1,542 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::de::{
+    value::StrDeserializer, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess,
+    IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde_json::Value;
+
+use crate::deserialization;
+
+/// The maximum length of the JSON samples kept in a report.
+const MAX_SAMPLE_LEN: usize = 200;
+
+/// Records where API responses differ from the models, to tell when the crate needs updating.
+///
+/// The detector is cheap to clone: all clones share the same report.
+#[derive(Clone, Debug, Default)]
+pub struct SchemaDriftDetector {
+    report: Arc<Mutex<DriftReport>>,
+}
+
+/// Represents the differences between the API responses and the models, per endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DriftReport {
+    /// The drift of each endpoint, keyed by path template, e.g. `/clans/{tag}/members`.
+    pub endpoints: BTreeMap<String, EndpointDrift>,
+}
+
+/// Represents the differences between the responses of one endpoint and its model.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EndpointDrift {
+    /// The number of responses checked.
+    pub responses: u64,
+    /// The JSON paths the model does not know, e.g. `items[].newField`.
+    pub unknown_fields: BTreeMap<String, DriftEntry>,
+    /// The JSON paths the model requires but the responses did not contain. Fields that are
+    /// optional or have a default are not reported.
+    pub missing_fields: BTreeMap<String, DriftEntry>,
+}
+
+/// Represents how often a difference was seen, with a sample of the first occurrence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DriftEntry {
+    /// The number of times the difference was seen.
+    pub count: u64,
+    /// When the difference was first seen.
+    pub first_seen: SystemTime,
+    /// The first unknown value, or the first object missing the field, as truncated JSON.
+    pub sample: String,
+}
+
+impl DriftReport {
+    /// Returns whether no differences were found.
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.values().all(|endpoint| {
+            endpoint.unknown_fields.is_empty() && endpoint.missing_fields.is_empty()
+        })
+    }
+}
+
+impl SchemaDriftDetector {
+    /// Creates a detector with an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the report.
+    pub fn report(&self) -> DriftReport {
+        self.report.lock().unwrap().clone()
+    }
+
+    /// Clears the report.
+    pub fn clear(&self) {
+        *self.report.lock().unwrap() = DriftReport::default();
+    }
+
+    /// Compares a response body against the model `T` and records the differences.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The path template of the endpoint, see `endpoint_template`.
+    /// * `text` - The JSON body of the response.
+    pub fn observe<T>(&self, endpoint: &str, text: &str)
+    where
+        T: DeserializeOwned,
+    {
+        let Ok(value) = serde_json::from_str::<Value>(text) else {
+            return;
+        };
+
+        let mut findings = Findings::default();
+        let _ = T::deserialize(Probe {
+            value: &value,
+            path: String::new(),
+            findings: &mut findings,
+        });
+
+        let (_, missing) = deserialization::fill_missing_fields::<T>(&mut value.clone());
+        let missing = missing
+            .into_iter()
+            .map(|field| (field.path, sample(&field.parent)))
+            .collect();
+
+        let now = SystemTime::now();
+        let mut report = self.report.lock().unwrap();
+        let endpoint = report.endpoints.entry(endpoint.to_string()).or_default();
+
+        endpoint.responses += 1;
+        record(&mut endpoint.unknown_fields, findings.unknown, now);
+        record(&mut endpoint.missing_fields, missing, now);
+    }
+}
+
+fn record(
+    entries: &mut BTreeMap<String, DriftEntry>,
+    found: Vec<(String, String)>,
+    now: SystemTime,
+) {
+    for (path, sample) in found {
+        entries
+            .entry(path)
+            .and_modify(|entry| entry.count += 1)
+            .or_insert(DriftEntry {
+                count: 1,
+                first_seen: now,
+                sample,
+            });
+    }
+}
+
+/// Returns the path template of a request path, used to group the report by endpoint.
+///
+/// Tags become `{tag}`, numeric ids `{id}` and season ids such as `2023-05` become `{seasonId}`,
+/// e.g. `/clans/%232LUGVU89Q/members` becomes `/clans/{tag}/members`.
+pub fn endpoint_template(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+
+    path.split('/')
+        .map(|segment| {
+            let is_season = segment.len() == 7
+                && segment.as_bytes()[4] == b'-'
+                && segment.replace('-', "").bytes().all(|b| b.is_ascii_digit());
+
+            if segment.starts_with("%23") || segment.starts_with('#') {
+                "{tag}"
+            } else if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+                "{id}"
+            } else if is_season {
+                "{seasonId}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn sample(value: &Value) -> String {
+    let mut sample = value.to_string();
+
+    if sample.len() > MAX_SAMPLE_LEN {
+        let mut end = MAX_SAMPLE_LEN;
+        while !sample.is_char_boundary(end) {
+            end -= 1;
+        }
+        sample.truncate(end);
+        sample.push('…');
+    }
+
+    sample
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[derive(Default)]
+struct Findings {
+    unknown: Vec<(String, String)>,
+}
+
+/// Walks a JSON value the way the model deserializes it, noting the fields each struct does not know.
+struct Probe<'a, 'f> {
+    value: &'a Value,
+    path: String,
+    findings: &'f mut Findings,
+}
+
+impl<'de, 'a, 'f> Deserializer<'de> for Probe<'a, 'f> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(value) => visitor.visit_bool(*value),
+            Value::Number(number) => {
+                if let Some(value) = number.as_u64() {
+                    visitor.visit_u64(value)
+                } else if let Some(value) = number.as_i64() {
+                    visitor.visit_i64(value)
+                } else {
+                    visitor.visit_f64(number.as_f64().unwrap_or_default())
+                }
+            }
+            Value::String(value) => visitor.visit_str(value),
+            Value::Array(items) => visitor.visit_seq(ProbeSeq {
+                items: items.iter(),
+                path: format!("{}[]", self.path),
+                findings: self.findings,
+            }),
+            Value::Object(map) => visitor.visit_map(ProbeMap {
+                entries: map.iter(),
+                value: None,
+                path: self.path,
+                fields: None,
+                findings: self.findings,
+            }),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let Value::Object(map) = self.value else {
+            return self.deserialize_any(visitor);
+        };
+
+        visitor.visit_map(ProbeMap {
+            entries: map.iter(),
+            value: None,
+            path: self.path,
+            fields: Some(fields),
+            findings: self.findings,
+        })
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::String(variant) => visitor.visit_enum(UnitVariant(variant)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+struct ProbeSeq<'a, 'f> {
+    items: std::slice::Iter<'a, Value>,
+    path: String,
+    findings: &'f mut Findings,
+}
+
+impl<'de, 'a, 'f> SeqAccess<'de> for ProbeSeq<'a, 'f> {
+    type Error = serde::de::value::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some(value) => seed
+                .deserialize(Probe {
+                    value,
+                    path: self.path.clone(),
+                    findings: self.findings,
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct ProbeMap<'a, 'f> {
+    entries: serde_json::map::Iter<'a>,
+    value: Option<(&'a String, &'a Value)>,
+    path: String,
+    /// The fields of the struct being deserialized, or `None` for maps.
+    fields: Option<&'static [&'static str]>,
+    findings: &'f mut Findings,
+}
+
+impl<'de, 'a, 'f> MapAccess<'de> for ProbeMap<'a, 'f> {
+    type Error = serde::de::value::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        if let Some(fields) = self.fields {
+            if !fields.contains(&key.as_str()) {
+                self.findings
+                    .unknown
+                    .push((join(&self.path, key), sample(value)));
+            }
+        }
+
+        self.value = Some((key, value));
+        let key: StrDeserializer<'_, Self::Error> = key.as_str().into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let Some((key, value)) = self.value.take() else {
+            return Err(serde::de::Error::custom("value requested before key"));
+        };
+
+        let path = match self.fields {
+            Some(_) => join(&self.path, key),
+            None => join(&self.path, "{}"),
+        };
+
+        seed.deserialize(Probe {
+            value,
+            path,
+            findings: self.findings,
+        })
+    }
+}
+
+struct UnitVariant<'a>(&'a str);
+
+impl<'de, 'a> EnumAccess<'de> for UnitVariant<'a> {
+    type Error = serde::de::value::Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant: StrDeserializer<'_, Self::Error> = self.0.into_deserializer();
+        seed.deserialize(variant).map(|variant| (variant, self))
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for UnitVariant<'a> {
+    type Error = serde::de::value::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        Err(serde::de::Error::custom("expected a unit variant"))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(serde::de::Error::custom("expected a unit variant"))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(serde::de::Error::custom("expected a unit variant"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::client::{CoCClient, PagedResponse};
+    use crate::deserialization::DeserializationMode;
+    use crate::testing::{MockResponse, MockServer};
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct Member {
+        name: String,
+        trophies: i64,
+        league: Option<League>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct League {
+        id: i64,
+        name: String,
+    }
+
+    #[test]
+    fn unknown_and_missing_fields_are_reported() {
+        let detector = SchemaDriftDetector::new();
+        let body = r#"{"items":[{"name":"A","trophies":1,"league":{"id":1,"name":"L","tier":3},"newField":true},{"name":"B","trophies":2,"newField":false}]}"#;
+
+        detector.observe::<PagedResponse<Member>>("/clans/{tag}/members", body);
+        detector.observe::<PagedResponse<Member>>("/clans/{tag}/members", body);
+
+        let report = detector.report();
+        let endpoint = &report.endpoints["/clans/{tag}/members"];
+
+        assert_eq!(endpoint.responses, 2);
+        assert_eq!(endpoint.unknown_fields["items[].newField"].count, 4);
+        assert_eq!(endpoint.unknown_fields["items[].newField"].sample, "true");
+        assert_eq!(endpoint.unknown_fields["items[].league.tier"].count, 2);
+        assert!(endpoint.missing_fields.is_empty());
+        assert!(!report.is_empty());
+
+        detector.clear();
+        assert!(detector.report().is_empty());
+    }
+
+    #[test]
+    fn only_required_fields_are_reported_missing() {
+        let detector = SchemaDriftDetector::new();
+        let body = r#"{"items":[{"name":"A","league":{"id":1}},{"trophies":2}]}"#;
+
+        detector.observe::<PagedResponse<Member>>("/clans/{tag}/members", body);
+
+        let report = detector.report();
+        let endpoint = &report.endpoints["/clans/{tag}/members"];
+
+        assert_eq!(
+            endpoint.missing_fields.keys().collect::<Vec<_>>(),
+            vec!["items[].league.name", "items[].name", "items[].trophies"]
+        );
+        assert_eq!(
+            endpoint.missing_fields["items[].trophies"].sample,
+            r#"{"league":{"id":1},"name":"A"}"#
+        );
+        assert!(!endpoint.missing_fields.contains_key("paging"));
+        assert!(!endpoint.missing_fields.contains_key("items[].league"));
+    }
+
+    #[test]
+    fn matching_responses_have_no_drift() {
+        let detector = SchemaDriftDetector::new();
+
+        detector.observe::<League>("/leagues/{id}", r#"{"id":1,"name":"L"}"#);
+
+        assert!(detector.report().is_empty());
+        assert_eq!(detector.report().endpoints["/leagues/{id}"].responses, 1);
+    }
+
+    #[test]
+    fn paths_are_grouped_by_endpoint() {
+        assert_eq!(
+            endpoint_template("/v1/clans/%232LUGVU89Q/members?limit=5"),
+            "/v1/clans/{tag}/members"
+        );
+        assert_eq!(
+            endpoint_template("/v1/leagues/29000022/seasons/2023-05"),
+            "/v1/leagues/{id}/seasons/{seasonId}"
+        );
+        assert_eq!(
+            endpoint_template("/v1/locations/global/rankings/clans"),
+            "/v1/locations/global/rankings/clans"
+        );
+    }
+
+    #[tokio::test]
+    async fn client_reports_drift_per_endpoint() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(200, r#"{"id":29000022,"tier":9}"#)
+        })
+        .await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .deserialization_mode(DeserializationMode::Lenient)
+            .detect_schema_drift(true)
+            .build()
+            .unwrap();
+
+        client.get_league_info(29000022).await.unwrap();
+        client.get_league_info(29000000).await.unwrap();
+
+        let report = client.schema_drift_detector().unwrap().report();
+        let endpoint = &report.endpoints["/leagues/{id}"];
+
+        assert_eq!(endpoint.responses, 2);
+        assert_eq!(endpoint.unknown_fields["tier"].count, 2);
+        assert_eq!(endpoint.missing_fields["name"].count, 2);
+        assert!(!endpoint.missing_fields.contains_key("iconUrls"));
+    }
+}
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 
-use serde::{Deserialize, Serialize};
+use reqwest::StatusCode;
+use serde::Deserialize;
 
 #[derive(Debug)]
 /// Represents various error types that can occur in the Clash of Clans client.
@@ -11,14 +11,98 @@ pub enum CoCClientError {
     Request(reqwest::Error),
     /// Represents an error that occurred during deserialization.
     Deserlisation(serde_json::Error),
-    /// Represents an error that occurred on the client side.
-    ClientError(ClientError),
-    /// Represents an error that occurred on the server side.
-    ServerError(ServerError),
-    /// Represents an error where the client is missing in the CoCClient.
-    MissingClientError,
-    /// Represents an unknown error.
-    UnkownError,
+    /// Represents a request body that could not be serialized into JSON.
+    Serialization(serde_json::Error),
+    /// Represents a resource that does not exist (404 `notFound`).
+    NotFound(Box<ApiError>),
+    /// Represents a request rejected because the key is not allowed to access the resource (403 `accessDenied`).
+    AccessDenied(Box<ApiError>),
+    /// Represents a request sent from an IP address the key is not bound to (403 `accessDenied.invalidIp`).
+    InvalidIp(Box<ApiError>),
+    /// Represents a clan war log that is not public (403 `privateWarLog`).
+    PrivateWarLog(Box<ApiError>),
+    /// Represents a request throttled because too many requests were sent with the key (429 `requestThrottled`).
+    Throttled(Box<ApiError>),
+    /// Represents the API being down for maintenance (503 `inMaintenance`).
+    Maintenance(Box<ApiError>),
+    /// Represents any other error that occurred on the client side, e.g. a bad request (400).
+    ClientError(Box<ApiError>),
+    /// Represents any other error that occurred on the server side.
+    ServerError(Box<ApiError>),
+    /// Represents a response with a status code the API does not document, or without an error body.
+    UnexpectedStatus {
+        /// The status code of the response.
+        status_code: StatusCode,
+        /// The raw body of the response.
+        body: String,
+    },
+    /// Represents request parameters that were rejected before sending the request.
+    InvalidParameters(String),
+    /// Represents an invalid client configuration, such as a malformed base URL.
+    InvalidConfiguration(String),
+    /// Represents an error reported by the developer portal, such as rejected credentials.
+    DeveloperPortal(String),
+    /// Represents a cassette that cannot be read or written, or a replayed request that was never recorded.
+    Cassette(String),
+}
+
+impl CoCClientError {
+    /// Creates the error matching an unsuccessful response, based on its status code and reason.
+    pub(crate) fn from_response(status_code: StatusCode, body: &str) -> Self {
+        let Some(api_error) = ApiError::parse(status_code, body).map(Box::new) else {
+            return CoCClientError::UnexpectedStatus {
+                status_code,
+                body: body.to_string(),
+            };
+        };
+
+        match (status_code, api_error.reason.as_str()) {
+            (StatusCode::NOT_FOUND, _) => CoCClientError::NotFound(api_error),
+            (StatusCode::FORBIDDEN, "accessDenied.invalidIp") => {
+                CoCClientError::InvalidIp(api_error)
+            }
+            (StatusCode::FORBIDDEN, "privateWarLog") => CoCClientError::PrivateWarLog(api_error),
+            (StatusCode::FORBIDDEN, _) => CoCClientError::AccessDenied(api_error),
+            (StatusCode::TOO_MANY_REQUESTS, _) => CoCClientError::Throttled(api_error),
+            (StatusCode::SERVICE_UNAVAILABLE, "inMaintenance") => {
+                CoCClientError::Maintenance(api_error)
+            }
+            (status_code, _) if status_code.is_client_error() => {
+                CoCClientError::ClientError(api_error)
+            }
+            (status_code, _) if status_code.is_server_error() => {
+                CoCClientError::ServerError(api_error)
+            }
+            (status_code, _) => CoCClientError::UnexpectedStatus {
+                status_code,
+                body: api_error.body,
+            },
+        }
+    }
+
+    /// Returns the HTTP status code of the response that caused the error, if a response was received.
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            CoCClientError::Request(err) => err.status(),
+            CoCClientError::UnexpectedStatus { status_code, .. } => Some(*status_code),
+            _ => self.api_error().map(|api_error| api_error.status_code),
+        }
+    }
+
+    /// Returns the error body returned by the API, if there is one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            CoCClientError::NotFound(api_error)
+            | CoCClientError::AccessDenied(api_error)
+            | CoCClientError::InvalidIp(api_error)
+            | CoCClientError::PrivateWarLog(api_error)
+            | CoCClientError::Throttled(api_error)
+            | CoCClientError::Maintenance(api_error)
+            | CoCClientError::ClientError(api_error)
+            | CoCClientError::ServerError(api_error) => Some(api_error.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for CoCClientError {
@@ -27,75 +111,193 @@ impl fmt::Display for CoCClientError {
         match self {
             CoCClientError::Request(err) => write!(f, "Request error: {}", err),
             CoCClientError::Deserlisation(err) => write!(f, "Deserialization error: {}", err),
+            CoCClientError::Serialization(err) => write!(f, "Serialization error: {}", err),
+            CoCClientError::NotFound(err) => write!(f, "Not found: {}", err),
+            CoCClientError::AccessDenied(err) => write!(f, "Access denied: {}", err),
+            CoCClientError::InvalidIp(err) => write!(f, "Invalid IP address: {}", err),
+            CoCClientError::PrivateWarLog(err) => write!(f, "Private war log: {}", err),
+            CoCClientError::Throttled(err) => write!(f, "Request throttled: {}", err),
+            CoCClientError::Maintenance(err) => write!(f, "In maintenance: {}", err),
             CoCClientError::ClientError(err) => write!(f, "{}", err),
             CoCClientError::ServerError(err) => write!(f, "{}", err),
-            CoCClientError::MissingClientError => write!(f, "Client is missing in CoCClient"),
-            CoCClientError::UnkownError => write!(f, "unkown error"),
+            CoCClientError::UnexpectedStatus { status_code, body } => {
+                write!(f, "Unexpected status {}: {}", status_code, body)
+            }
+            CoCClientError::InvalidParameters(reason) => {
+                write!(f, "Invalid parameters: {}", reason)
+            }
+            CoCClientError::InvalidConfiguration(reason) => {
+                write!(f, "Invalid configuration: {}", reason)
+            }
+            CoCClientError::DeveloperPortal(reason) => {
+                write!(f, "Developer portal error: {}", reason)
+            }
+            CoCClientError::Cassette(reason) => write!(f, "Cassette error: {}", reason),
         }
     }
 }
 
-/// Represents a client-side error in the Clash of Clans client.
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ClientError {
-    /// A string that describes the reason for the client error.
-    pub reason: String,
-    /// A string that provides additional information about the client error.
-    pub message: Option<String>,
-    /// An optional string that specifies the type of the client error.
-    pub r#type: Option<String>,
-    /// An optional HashMap that contains additional details about the client error.
-    pub detail: Option<HashMap<String, String>>,
+impl Error for CoCClientError {
+    /// Returns the underlying request, deserialization or serialization error.
+    ///
+    /// The `ApiError` of the API error variants is not a source, since their `Display` already
+    /// includes it. Use `api_error` to get it.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CoCClientError::Request(err) => Some(err),
+            CoCClientError::Deserlisation(err) => Some(err),
+            CoCClientError::Serialization(err) => Some(err),
+            _ => None,
+        }
+    }
 }
 
-/// Represents a server-side error in the Clash of Clans client.
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ServerError {
-    /// A string that describes the reason for the server error.
+/// Represents an error returned by the Clash of Clans API.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    /// The HTTP status code of the response.
+    pub status_code: StatusCode,
+    /// A string that describes the reason for the error, e.g. `notFound`.
     pub reason: String,
-    /// A string that provides additional information about the server error.
+    /// A string that provides additional information about the error.
     pub message: Option<String>,
-    /// An optional string that specifies the type of the server error.
+    /// An optional string that specifies the type of the error.
     pub r#type: Option<String>,
-    /// An optional HashMap that contains additional details about the server error.
-    pub detail: Option<HashMap<String, String>>,
+    /// Optional additional details about the error.
+    pub detail: Option<serde_json::Value>,
+    /// The raw body of the response.
+    pub body: String,
 }
 
-/// Represents an unknown error.
-#[derive(Debug)]
-pub struct UnkownError {}
+/// Represents a client-side error in the Clash of Clans client.
+#[deprecated(
+    since = "0.8.0",
+    note = "use `ApiError`, which every API error variant now carries"
+)]
+pub type ClientError = ApiError;
 
-impl Error for ClientError {}
+/// Represents a server-side error in the Clash of Clans client.
+#[deprecated(
+    since = "0.8.0",
+    note = "use `ApiError`, which every API error variant now carries"
+)]
+pub type ServerError = ApiError;
 
-impl Error for ServerError {}
+#[derive(Deserialize)]
+struct ErrorBody {
+    reason: String,
+    message: Option<String>,
+    r#type: Option<String>,
+    detail: Option<serde_json::Value>,
+}
 
-impl Error for UnkownError {}
+impl ApiError {
+    /// Parses the error body of a response, returning `None` if it is not an API error.
+    pub(crate) fn parse(status_code: StatusCode, body: &str) -> Option<Self> {
+        let error_body: ErrorBody = serde_json::from_str(body).ok()?;
 
-impl fmt::Display for ClientError {
-    /// Formats the `ClientError` as a string.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Client error: Reason={}, Message={:?}, Type={:?}, Detail={:?}",
-            self.reason, self.message, self.r#type, self.detail
-        )
+        Some(Self {
+            status_code,
+            reason: error_body.reason,
+            message: error_body.message,
+            r#type: error_body.r#type,
+            detail: error_body.detail,
+            body: body.to_string(),
+        })
     }
 }
 
-impl fmt::Display for ServerError {
-    /// Formats the `ServerError` as a string.
+impl Error for ApiError {}
+
+impl fmt::Display for ApiError {
+    /// Formats the `ApiError` as a string.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = if self.status_code.is_server_error() {
+            "Server"
+        } else {
+            "Client"
+        };
+
         write!(
             f,
-            "Server error: Reason={}, Message={:?}, Type={:?}, Detail={:?}",
-            self.reason, self.message, self.r#type, self.detail
+            "{} error ({}): Reason={}, Message={:?}, Type={:?}, Detail={:?}",
+            kind, self.status_code, self.reason, self.message, self.r#type, self.detail
         )
     }
 }
 
-impl fmt::Display for UnkownError {
-    /// Formats the `UnkownError` as a string.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Unkown error")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(status: u16, reason: &str) -> CoCClientError {
+        let body = format!(r#"{{"reason":"{}","message":"message"}}"#, reason);
+
+        CoCClientError::from_response(StatusCode::from_u16(status).unwrap(), &body)
+    }
+
+    #[test]
+    fn errors_are_mapped_from_reason_codes() {
+        assert!(matches!(
+            error(404, "notFound"),
+            CoCClientError::NotFound(_)
+        ));
+        assert!(matches!(
+            error(403, "accessDenied"),
+            CoCClientError::AccessDenied(_)
+        ));
+        assert!(matches!(
+            error(403, "accessDenied.invalidIp"),
+            CoCClientError::InvalidIp(_)
+        ));
+        assert!(matches!(
+            error(403, "privateWarLog"),
+            CoCClientError::PrivateWarLog(_)
+        ));
+        assert!(matches!(
+            error(429, "requestThrottled"),
+            CoCClientError::Throttled(_)
+        ));
+        assert!(matches!(
+            error(503, "inMaintenance"),
+            CoCClientError::Maintenance(_)
+        ));
+        assert!(matches!(
+            error(400, "badRequest"),
+            CoCClientError::ClientError(_)
+        ));
+        assert!(matches!(
+            error(500, "unknownException"),
+            CoCClientError::ServerError(_)
+        ));
+    }
+
+    #[test]
+    fn errors_keep_status_code_and_body() {
+        let err = error(403, "privateWarLog");
+        let api_error = err.api_error().unwrap();
+
+        assert_eq!(err.status_code(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(api_error.message.as_deref(), Some("message"));
+        assert!(api_error.body.contains("privateWarLog"));
+        assert!(err.to_string().contains("privateWarLog"));
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn unexpected_statuses_keep_status_code_and_body() {
+        let err =
+            CoCClientError::from_response(StatusCode::BAD_GATEWAY, "<html>Bad gateway</html>");
+
+        match &err {
+            CoCClientError::UnexpectedStatus { status_code, body } => {
+                assert_eq!(*status_code, StatusCode::BAD_GATEWAY);
+                assert_eq!(body, "<html>Bad gateway</html>");
+            }
+            _ => panic!("expected an unexpected status error, got {}", err),
+        }
+
+        assert_eq!(err.status_code(), Some(StatusCode::BAD_GATEWAY));
+        assert!(err.source().is_none());
     }
 }
@@ -0,0 +1,250 @@
+/// A clan, as returned by `/clans/{clanTag}`.
+pub const CLAN: &str = include_str!("../fixtures/clan.json");
+/// A page of clan search results, as returned by `/clans`.
+pub const CLAN_SEARCH: &str = include_str!("../fixtures/clan_search.json");
+/// A page of clan members, as returned by `/clans/{clanTag}/members`.
+pub const CLAN_MEMBERS: &str = include_str!("../fixtures/clan_members.json");
+/// A page of the war log, including a war league entry, as returned by `/clans/{clanTag}/warlog`.
+pub const CLAN_WAR_LOG: &str = include_str!("../fixtures/clan_war_log.json");
+/// The current war of a clan that is not in war.
+pub const CLAN_WAR_NOT_IN_WAR: &str = include_str!("../fixtures/clan_war_not_in_war.json");
+/// The current war of a clan in preparation day.
+pub const CLAN_WAR_PREPARATION: &str = include_str!("../fixtures/clan_war_preparation.json");
+/// The current war of a clan in battle day.
+pub const CLAN_WAR_IN_WAR: &str = include_str!("../fixtures/clan_war_in_war.json");
+/// The current war of a clan whose war has ended.
+pub const CLAN_WAR_ENDED: &str = include_str!("../fixtures/clan_war_ended.json");
+/// A war league group, as returned by `/clans/{clanTag}/currentwar/leaguegroup`.
+pub const CLAN_WAR_LEAGUE_GROUP: &str = include_str!("../fixtures/clan_war_league_group.json");
+/// A war league war, as returned by `/clanwarleagues/wars/{warTag}`.
+pub const CLAN_WAR_LEAGUE_WAR: &str = include_str!("../fixtures/clan_war_league_war.json");
+/// A page of raid seasons, as returned by `/clans/{clanTag}/capitalraidseasons`.
+pub const CLAN_CAPITAL_RAID_SEASONS: &str =
+    include_str!("../fixtures/clan_capital_raid_seasons.json");
+/// A player, as returned by `/players/{playerTag}`.
+pub const PLAYER: &str = include_str!("../fixtures/player.json");
+/// A successful token verification, as returned by `/players/{playerTag}/verifytoken`.
+pub const VERIFY_TOKEN: &str = include_str!("../fixtures/verify_token.json");
+/// A page of leagues, as returned by `/leagues`.
+pub const LEAGUES: &str = include_str!("../fixtures/leagues.json");
+/// A page of Legend League seasons, as returned by `/leagues/{leagueId}/seasons`.
+pub const LEAGUE_SEASONS: &str = include_str!("../fixtures/league_seasons.json");
+/// A page of Legend League season rankings, as returned by `/leagues/{leagueId}/seasons/{seasonId}`.
+pub const LEAGUE_SEASON_RANKINGS: &str = include_str!("../fixtures/league_season_rankings.json");
+/// A page of war leagues, as returned by `/warleagues`.
+pub const WAR_LEAGUES: &str = include_str!("../fixtures/war_leagues.json");
+/// A page of builder base leagues, as returned by `/builderbaseleagues`.
+pub const BUILDER_BASE_LEAGUES: &str = include_str!("../fixtures/builder_base_leagues.json");
+/// A page of capital leagues, as returned by `/capitalleagues`.
+pub const CAPITAL_LEAGUES: &str = include_str!("../fixtures/capital_leagues.json");
+/// A page of locations, as returned by `/locations`.
+pub const LOCATIONS: &str = include_str!("../fixtures/locations.json");
+/// A page of clan rankings, as returned by `/locations/{locationId}/rankings/clans`.
+pub const LOCATION_CLAN_RANKINGS: &str = include_str!("../fixtures/location_clan_rankings.json");
+/// A page of player rankings, as returned by `/locations/{locationId}/rankings/players`.
+pub const LOCATION_PLAYER_RANKINGS: &str =
+    include_str!("../fixtures/location_player_rankings.json");
+/// A page of player builder base rankings, as returned by `/locations/{locationId}/rankings/players-builder-base`.
+pub const LOCATION_PLAYER_BUILDER_BASE_RANKINGS: &str =
+    include_str!("../fixtures/location_player_builder_base_rankings.json");
+/// A page of clan builder base rankings, as returned by `/locations/{locationId}/rankings/clans-builder-base`.
+pub const LOCATION_CLAN_BUILDER_BASE_RANKINGS: &str =
+    include_str!("../fixtures/location_clan_builder_base_rankings.json");
+/// A page of clan capital rankings, as returned by `/locations/{locationId}/rankings/capitals`.
+pub const LOCATION_CAPITAL_RANKINGS: &str =
+    include_str!("../fixtures/location_capital_rankings.json");
+/// A page of clan labels, as returned by `/labels/clans`.
+pub const LABELS_CLANS: &str = include_str!("../fixtures/labels_clans.json");
+/// A page of player labels, as returned by `/labels/players`.
+pub const LABELS_PLAYERS: &str = include_str!("../fixtures/labels_players.json");
+/// The current gold pass season, as returned by `/goldpass/seasons/current`.
+pub const GOLD_PASS: &str = include_str!("../fixtures/goldpass.json");
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::clans::models::{
+        Clan, ClanCapitalRaidSeason, ClanMember, ClanWar, ClanWarLeagueGroup, ClanWarLogEntry,
+        ClanWarState,
+    };
+    use crate::client::PagedResponse;
+    use crate::deserialization::{self, DeserializationMode};
+    use crate::drift::SchemaDriftDetector;
+    use crate::goldpass::models::GoldPassSeason;
+    use crate::labels::models::Label;
+    use crate::leagues::models::{League, LeagueInfo, PlayerRanking, SeasonInfo};
+    use crate::locations::models::{
+        ClanBuilderBaseRanking, ClanCapitalRanking, ClanRanking, Location, PlayerBuilderBaseRanking,
+    };
+    use crate::players::models::{Player, VerifyTokenResponse};
+
+    /// Deserializes `json` strictly into `T`, and checks that serializing it back keeps every
+    /// value of the fixture, and that a second round trip is identical to the first.
+    fn assert_golden<T>(name: &str, json: &str) -> T
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        let detector = SchemaDriftDetector::new();
+        detector.observe::<T>(name, json);
+        let report = detector.report();
+        let unknown: Vec<&String> = report
+            .endpoints
+            .values()
+            .flat_map(|drift| drift.unknown_fields.keys())
+            .collect();
+        assert!(unknown.is_empty(), "{}: unknown fields {:?}", name, unknown);
+
+        let data: T = deserialization::from_str(json, DeserializationMode::Strict)
+            .unwrap_or_else(|err| panic!("{}: {}", name, err));
+
+        let expected: Value = serde_json::from_str(json).unwrap();
+        let serialized = serde_json::to_value(&data).unwrap();
+        assert_contains(name, "$", &expected, &serialized);
+
+        let again: T = serde_json::from_value(serialized.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&again).unwrap(),
+            serialized,
+            "{}: second round trip differs",
+            name
+        );
+
+        data
+    }
+
+    /// Asserts that every value of `expected` is present in `actual`, ignoring extra keys.
+    fn assert_contains(name: &str, path: &str, expected: &Value, actual: &Value) {
+        match (expected, actual) {
+            (Value::Object(expected), Value::Object(actual)) => {
+                for (key, value) in expected {
+                    let path = format!("{}.{}", path, key);
+                    match actual.get(key) {
+                        Some(actual) => assert_contains(name, &path, value, actual),
+                        None => panic!("{}: {} was dropped", name, path),
+                    }
+                }
+            }
+            (Value::Array(expected), Value::Array(actual)) => {
+                assert_eq!(expected.len(), actual.len(), "{}: {}", name, path);
+                for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                    assert_contains(name, &format!("{}[{}]", path, index), expected, actual);
+                }
+            }
+            (Value::Number(expected), Value::Number(actual)) => {
+                assert_eq!(expected.as_f64(), actual.as_f64(), "{}: {}", name, path);
+            }
+            (expected, actual) => assert_eq!(expected, actual, "{}: {}", name, path),
+        }
+    }
+
+    #[test]
+    fn clan_fixtures_round_trip() {
+        let clan: Clan = assert_golden("clan", CLAN);
+        assert_eq!(clan.member_list.len(), 2);
+        assert_eq!(clan.required_town_hall_level, Some(12));
+
+        let search: PagedResponse<Clan> = assert_golden("clan_search", CLAN_SEARCH);
+        assert!(search.items[0].member_list.is_empty());
+        assert_eq!(search.next_cursor(), Some("eyJwb3MiOjF9"));
+
+        assert_golden::<PagedResponse<ClanMember>>("clan_members", CLAN_MEMBERS);
+    }
+
+    #[test]
+    fn war_fixtures_round_trip() {
+        let log: PagedResponse<ClanWarLogEntry> = assert_golden("clan_war_log", CLAN_WAR_LOG);
+        assert!(log.items[1].result.is_none());
+
+        let fixtures = [
+            (
+                "clan_war_not_in_war",
+                CLAN_WAR_NOT_IN_WAR,
+                ClanWarState::NotInWar,
+            ),
+            (
+                "clan_war_preparation",
+                CLAN_WAR_PREPARATION,
+                ClanWarState::Preparation,
+            ),
+            ("clan_war_in_war", CLAN_WAR_IN_WAR, ClanWarState::InWar),
+            ("clan_war_ended", CLAN_WAR_ENDED, ClanWarState::WarEnded),
+            (
+                "clan_war_league_war",
+                CLAN_WAR_LEAGUE_WAR,
+                ClanWarState::InWar,
+            ),
+        ];
+
+        for (name, json, state) in fixtures {
+            let war: ClanWar = assert_golden(name, json);
+            assert_eq!(war.state, state, "{}", name);
+        }
+
+        let group: ClanWarLeagueGroup =
+            assert_golden("clan_war_league_group", CLAN_WAR_LEAGUE_GROUP);
+        assert_eq!(group.rounds.unwrap()[0].war_tags.len(), 4);
+
+        assert_golden::<PagedResponse<ClanCapitalRaidSeason>>(
+            "clan_capital_raid_seasons",
+            CLAN_CAPITAL_RAID_SEASONS,
+        );
+    }
+
+    #[test]
+    fn player_fixtures_round_trip() {
+        let player: Player = assert_golden("player", PLAYER);
+        assert_eq!(player.achievements.len(), 2);
+
+        let response: VerifyTokenResponse = assert_golden("verify_token", VERIFY_TOKEN);
+        assert!(response.is_valid());
+    }
+
+    #[test]
+    fn league_fixtures_round_trip() {
+        assert_golden::<PagedResponse<League>>("leagues", LEAGUES);
+        assert_golden::<PagedResponse<LeagueInfo>>("war_leagues", WAR_LEAGUES);
+        assert_golden::<PagedResponse<LeagueInfo>>("builder_base_leagues", BUILDER_BASE_LEAGUES);
+        assert_golden::<PagedResponse<LeagueInfo>>("capital_leagues", CAPITAL_LEAGUES);
+        assert_golden::<PagedResponse<SeasonInfo>>("league_seasons", LEAGUE_SEASONS);
+        assert_golden::<PagedResponse<PlayerRanking>>(
+            "league_season_rankings",
+            LEAGUE_SEASON_RANKINGS,
+        );
+    }
+
+    #[test]
+    fn location_fixtures_round_trip() {
+        assert_golden::<PagedResponse<Location>>("locations", LOCATIONS);
+        assert_golden::<PagedResponse<ClanRanking>>(
+            "location_clan_rankings",
+            LOCATION_CLAN_RANKINGS,
+        );
+        assert_golden::<PagedResponse<PlayerRanking>>(
+            "location_player_rankings",
+            LOCATION_PLAYER_RANKINGS,
+        );
+        assert_golden::<PagedResponse<PlayerBuilderBaseRanking>>(
+            "location_player_builder_base_rankings",
+            LOCATION_PLAYER_BUILDER_BASE_RANKINGS,
+        );
+        assert_golden::<PagedResponse<ClanBuilderBaseRanking>>(
+            "location_clan_builder_base_rankings",
+            LOCATION_CLAN_BUILDER_BASE_RANKINGS,
+        );
+        assert_golden::<PagedResponse<ClanCapitalRanking>>(
+            "location_capital_rankings",
+            LOCATION_CAPITAL_RANKINGS,
+        );
+    }
+
+    #[test]
+    fn label_and_gold_pass_fixtures_round_trip() {
+        assert_golden::<PagedResponse<Label>>("labels_clans", LABELS_CLANS);
+        assert_golden::<PagedResponse<Label>>("labels_players", LABELS_PLAYERS);
+        assert_golden::<GoldPassSeason>("goldpass", GOLD_PASS);
+    }
+}
@@ -1,9 +1,12 @@
 use serde::{Serialize, Deserialize};
 
+use crate::timestamp::{self, Timestamp};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GoldPassSeason {
-    pub start_time: String,
-    pub end_time: String
-}
\ No newline at end of file
+    #[serde(with = "timestamp::api_format")]
+    pub start_time: Timestamp,
+    #[serde(with = "timestamp::api_format")]
+    pub end_time: Timestamp,
+}
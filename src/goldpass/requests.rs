@@ -1,34 +1,41 @@
-use crate::{client::CoCClient, errors::CoCClientError};
+use crate::{client::CoCClient, errors::CoCClientError, response::ApiResponse};
 
 use super::models::GoldPassSeason;
 
 impl CoCClient {
-    pub async fn get_current_gold_pass(self: Self) -> Result<GoldPassSeason, CoCClientError> {
-        let path = format!("{}/goldpass/seasons/current", self.url);
+    pub async fn get_current_gold_pass(&self) -> Result<GoldPassSeason, CoCClientError> {
+        let path = format!("{}/goldpass/seasons/current", self.url());
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
 
         CoCClient::handle_response(client_response).await
     }
+
+    pub async fn get_current_gold_pass_response(
+        &self,
+    ) -> Result<ApiResponse<GoldPassSeason>, CoCClientError> {
+        let path = format!("{}/goldpass/seasons/current", self.url());
+
+        self.get_api_response(&path, Vec::new()).await
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
+    use crate::testing::MockServer;
 
-    fn set_up_client() -> CoCClient {
-        let bearer_token = std::env::var("BEARER_TOKEN").expect("env var BEARER_TOKEN not set");
-
-        CoCClient::new(bearer_token, None)
+    async fn set_up_client() -> CoCClient {
+        MockServer::start().await.client().unwrap()
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_current_gold_pass() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_current_gold_pass().await {
             Ok(_) => {
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Represents a label of a clan or player, as returned by the label catalogs.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    pub name: String,
+    pub id: i64,
+    pub icon_urls: Option<HashMap<String, String>>,
+}
+
+impl Label {
+    /// Returns the id of the label as a clan label.
+    pub fn clan_label_id(&self) -> ClanLabelId {
+        ClanLabelId::from(self.id)
+    }
+
+    /// Returns the id of the label as a player label.
+    pub fn player_label_id(&self) -> PlayerLabelId {
+        PlayerLabelId::from(self.id)
+    }
+}
+
+/// Defines a label id enum with a variant for each known label and an `Unknown` fallback.
+macro_rules! label_ids {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($variant:ident = $id:literal => $label:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[serde(from = "i64", into = "i64")]
+        pub enum $name {
+            $(
+                #[doc = concat!("The `", $label, "` label.")]
+                $variant,
+            )*
+            /// A label added to the game after this version of the crate.
+            Unknown(i64),
+        }
+
+        impl $name {
+            /// Every known label.
+            pub const KNOWN: &'static [$name] = &[$($name::$variant,)*];
+
+            /// Returns the numeric id of the label.
+            pub fn id(self) -> i64 {
+                match self {
+                    $($name::$variant => $id,)*
+                    $name::Unknown(id) => id,
+                }
+            }
+
+            /// Returns the English name of the label, or `None` for unknown labels.
+            pub fn name(self) -> Option<&'static str> {
+                match self {
+                    $($name::$variant => Some($label),)*
+                    $name::Unknown(_) => None,
+                }
+            }
+
+            /// Finds a known label by its English name, ignoring case.
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::KNOWN
+                    .iter()
+                    .copied()
+                    .find(|label| label.name().is_some_and(|label| label.eq_ignore_ascii_case(name.trim())))
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(id: i64) -> Self {
+                match id {
+                    $($id => $name::$variant,)*
+                    id => $name::Unknown(id),
+                }
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(label: $name) -> Self {
+                label.id()
+            }
+        }
+    };
+}
+
+label_ids! {
+    /// Represents the id of a clan label, as used by the clan search `labelIds` filter.
+    ClanLabelId {
+        ClanWars = 56000000 => "Clan Wars",
+        ClanWarLeague = 56000001 => "Clan War League",
+        TrophyPushing = 56000002 => "Trophy Pushing",
+        FriendlyWars = 56000003 => "Friendly Wars",
+        ClanGames = 56000004 => "Clan Games",
+        BuilderBase = 56000005 => "Builder Base",
+        BaseDesigning = 56000006 => "Base Designing",
+        International = 56000007 => "International",
+        Farming = 56000008 => "Farming",
+        Donations = 56000009 => "Donations",
+        Friendly = 56000010 => "Friendly",
+        Talkative = 56000011 => "Talkative",
+        Underdog = 56000012 => "Underdog",
+        Relaxed = 56000013 => "Relaxed",
+        Competitive = 56000014 => "Competitive",
+        NewbieFriendly = 56000015 => "Newbie Friendly",
+        ClanCapital = 56000016 => "Clan Capital",
+    }
+}
+
+label_ids! {
+    /// Represents the id of a player label.
+    PlayerLabelId {
+        ClanWars = 57000000 => "Clan Wars",
+        ClanWarLeague = 57000001 => "Clan War League",
+        TrophyPushing = 57000002 => "Trophy Pushing",
+        FriendlyWars = 57000003 => "Friendly Wars",
+        ClanGames = 57000004 => "Clan Games",
+        BuilderBase = 57000005 => "Builder Base",
+        BaseDesigning = 57000006 => "Base Designing",
+        Farming = 57000007 => "Farming",
+        ActiveDonator = 57000008 => "Active Donator",
+        ActiveDaily = 57000009 => "Active Daily",
+        HungryLearner = 57000010 => "Hungry Learner",
+        Friendly = 57000011 => "Friendly",
+        Talkative = 57000012 => "Talkative",
+        Teacher = 57000013 => "Teacher",
+        Competitive = 57000014 => "Competitive",
+        Veteran = 57000015 => "Veteran",
+        Newbie = 57000016 => "Newbie",
+        AmateurAttacker = 57000017 => "Amateur Attacker",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_ids_convert_from_and_to_numbers() {
+        assert_eq!(ClanLabelId::from(56000007), ClanLabelId::International);
+        assert_eq!(ClanLabelId::International.id(), 56000007);
+        assert_eq!(ClanLabelId::from(56999999), ClanLabelId::Unknown(56999999));
+        assert_eq!(ClanLabelId::Unknown(56999999).id(), 56999999);
+        assert_eq!(PlayerLabelId::from(57000013), PlayerLabelId::Teacher);
+    }
+
+    #[test]
+    fn label_ids_are_found_by_name() {
+        assert_eq!(
+            ClanLabelId::from_name("newbie friendly"),
+            Some(ClanLabelId::NewbieFriendly)
+        );
+        assert_eq!(ClanLabelId::from_name("Teacher"), None);
+        assert_eq!(ClanLabelId::Unknown(1).name(), None);
+    }
+
+    #[test]
+    fn label_ids_deserialize_from_numbers() {
+        let labels: Vec<PlayerLabelId> = serde_json::from_str("[57000009, 57999999]").unwrap();
+
+        assert_eq!(
+            labels,
+            vec![PlayerLabelId::ActiveDaily, PlayerLabelId::Unknown(57999999)]
+        );
+        assert_eq!(
+            serde_json::to_string(&labels).unwrap(),
+            "[57000009,57999999]"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    client::{CoCClient, PagedResponse},
+    errors::CoCClientError,
+    params::{PageParams, QueryParams},
+};
+
+use super::models::Label;
+
+impl CoCClient {
+    /// Retrieves the catalog of clan labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the clan labels as `PagedResponse<Label>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_clan_labels(
+        &self,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<Label>, CoCClientError> {
+        let path = format!("{}/labels/clans", self.url());
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves the catalog of player labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the player labels as `PagedResponse<Label>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_player_labels(
+        &self,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<Label>, CoCClientError> {
+        let path = format!("{}/labels/players", self.url());
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::labels::models::ClanLabelId;
+    use crate::testing::{MockResponse, MockServer};
+
+    async fn set_up_client() -> CoCClient {
+        MockServer::start().await.client().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_clan_labels() {
+        let client = set_up_client().await;
+
+        if let Err(err) = client.get_clan_labels(None).await {
+            panic!("{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_player_labels() {
+        let client = set_up_client().await;
+
+        if let Err(err) = client.get_player_labels(None).await {
+            panic!("{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_clan_labels_offline() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                200,
+                r#"{"items":[{"id":56000000,"name":"Clan Wars","iconUrls":{}},{"id":56000099,"name":"Brand New"}],"paging":{"cursors":{}}}"#,
+            )
+        })
+        .await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let labels = client.get_clan_labels(None).await.unwrap();
+
+        assert_eq!(server.requests()[0].path, "/v1/labels/clans");
+        assert_eq!(labels.items[0].clan_label_id(), ClanLabelId::ClanWars);
+        assert_eq!(
+            labels.items[1].clan_label_id(),
+            ClanLabelId::Unknown(56000099)
+        );
+    }
+}
@@ -2,14 +2,20 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::tag::Tag;
+
+/// The id of the Legend League, the only league with season rankings.
+pub const LEGEND_LEAGUE_ID: i64 = 29000022;
+
+/// Represents a player in the rankings of a location or a Legend League season.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayerRanking {
     pub league: Option<League>,
-    pub clan: PlayerRankingClan,
+    pub clan: Option<PlayerRankingClan>,
     pub attack_wins: i64,
     pub defense_wins: i64,
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
     pub exp_level: i64,
     pub rank: i64,
@@ -17,28 +23,32 @@ pub struct PlayerRanking {
     pub trophies: i64,
 }
 
+/// Represents the clan of a ranked player.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayerRankingClan {
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
     pub badge_urls: HashMap<String, String>,
 }
 
+/// Represents a home village league in Clash of Clans.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct League {
     pub name: String,
-    pub id: u32,
+    pub id: i64,
     pub icon_urls: Option<HashMap<String, String>>,
 }
 
+/// Represents a war, builder base or clan capital league, which have no icons.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LeagueInfo {
-    pub id: u32,
+    pub id: i64,
     pub name: String,
 }
 
+/// Represents a Legend League season.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SeasonInfo {
     pub id: String,
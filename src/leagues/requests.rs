@@ -1,20 +1,34 @@
-use std::collections::HashMap;
+use futures::stream::BoxStream;
 
 use crate::{
     client::{CoCClient, PagedResponse},
     errors::CoCClientError,
+    pagination::PaginationOptions,
+    params::{PageParams, QueryParams},
 };
 
 use super::models::{League, LeagueInfo, PlayerRanking, SeasonInfo};
 
 impl CoCClient {
+    /// Retrieves the list of clan capital leagues.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the clan capital leagues as `PagedResponse<LeagueInfo>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_capital_leagues(
-        self: Self,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<LeagueInfo>, CoCClientError> {
-        let path = format!("{}/capitalleagues/", self.url);
+        let path = format!("{}/capitalleagues", self.url());
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -22,13 +36,25 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves the list of home village leagues.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the leagues as `PagedResponse<League>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_leagues(
-        self: Self,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<League>, CoCClientError> {
-        let path = format!("{}/capitalleagues/", self.url);
+        let path = format!("{}/leagues", self.url());
+
+        let query = params.unwrap_or_default().to_query()?;
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -36,13 +62,23 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves information about a clan capital league.
+    ///
+    /// # Arguments
+    ///
+    /// * `league_id` - The id of the league.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the clan capital league as `LeagueInfo` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_capital_league_info(
-        self: Self,
-        league_id: u32,
+        &self,
+        league_id: i64,
     ) -> Result<LeagueInfo, CoCClientError> {
-        let path = format!("{}/capitalleagues/{}", self.url, league_id);
+        let path = format!("{}/capitalleagues/{}", self.url(), league_id);
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -50,13 +86,25 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves the list of builder base leagues.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the builder base leagues as `PagedResponse<LeagueInfo>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_builder_base_leagues(
-        self: Self,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<LeagueInfo>, CoCClientError> {
-        let path = format!("{}/builderbaseleagues/", self.url);
+        let path = format!("{}/builderbaseleagues", self.url());
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -64,13 +112,23 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves information about a builder base league.
+    ///
+    /// # Arguments
+    ///
+    /// * `league_id` - The id of the league.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the builder base league as `LeagueInfo` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_builder_base_league_info(
-        self: Self,
-        league_id: u32,
+        &self,
+        league_id: i64,
     ) -> Result<LeagueInfo, CoCClientError> {
-        let path = format!("{}/builderbaseleagues/{}", self.url, league_id);
+        let path = format!("{}/builderbaseleagues/{}", self.url(), league_id);
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -78,10 +136,20 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
-    pub async fn get_league_info(self: Self, league_id: u32) -> Result<League, CoCClientError> {
-        let path = format!("{}/leagues/{}", self.url, league_id);
-
-        let client_response = match self.send_get_request(&path, None).await {
+    /// Retrieves information about a home village league.
+    ///
+    /// # Arguments
+    ///
+    /// * `league_id` - The id of the league.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the league as `League` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_league_info(&self, league_id: i64) -> Result<League, CoCClientError> {
+        let path = format!("{}/leagues/{}", self.url(), league_id);
+
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -89,13 +157,25 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves the list of clan war leagues.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the war leagues as `PagedResponse<LeagueInfo>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_war_leagues(
-        self: Self,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<LeagueInfo>, CoCClientError> {
-        let path = format!("{}/warleagues/", self.url);
+        let path = format!("{}/warleagues", self.url());
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -103,10 +183,20 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
-    pub async fn get_war_league_info(self: Self, league_id: u32) -> Result<League, CoCClientError> {
-        let path = format!("{}/warleagues/{}", self.url, league_id);
-
-        let client_response = match self.send_get_request(&path, None).await {
+    /// Retrieves information about a clan war league.
+    ///
+    /// # Arguments
+    ///
+    /// * `league_id` - The id of the league.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the war league as `LeagueInfo` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_war_league_info(&self, league_id: i64) -> Result<LeagueInfo, CoCClientError> {
+        let path = format!("{}/warleagues/{}", self.url(), league_id);
+
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -114,14 +204,27 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves the seasons of a league. Only the Legend League has seasons.
+    ///
+    /// # Arguments
+    ///
+    /// * `league_id` - The id of the league, e.g. `LEGEND_LEAGUE_ID`.
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the seasons as `PagedResponse<SeasonInfo>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_league_seasons(
-        self: Self,
-        league_id: u32,
-        params: Option<HashMap<&str, &str>>,
+        &self,
+        league_id: i64,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<SeasonInfo>, CoCClientError> {
-        let path = format!("{}/leagues/{}/seasons", self.url, league_id);
+        let path = format!("{}/leagues/{}/seasons", self.url(), league_id);
+
+        let query = params.unwrap_or_default().to_query()?;
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -129,37 +232,73 @@ impl CoCClient {
         CoCClient::handle_response(client_response).await
     }
 
+    /// Retrieves the player rankings of a Legend League season.
+    ///
+    /// # Arguments
+    ///
+    /// * `league_id` - The id of the league, e.g. `LEGEND_LEAGUE_ID`.
+    /// * `season_id` - The id of the season, e.g. `2023-05`.
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the player rankings as `PagedResponse<PlayerRanking>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
     pub async fn get_league_season_rankings(
-        self: Self,
-        league_id: u32,
+        &self,
+        league_id: i64,
         season_id: &str,
-        params: Option<HashMap<&str, &str>>,
+        params: Option<PageParams>,
     ) -> Result<PagedResponse<PlayerRanking>, CoCClientError> {
-        let path = format!("{}/leagues/{}/seasons/{}", self.url, league_id, season_id);
+        let path = format!("{}/leagues/{}/seasons/{}", self.url(), league_id, season_id);
 
-        let client_response = match self.send_get_request(&path, params).await {
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
 
         CoCClient::handle_response(client_response).await
     }
+
+    /// Streams the full player rankings of a league season, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `league_id` - The id of the league, e.g. `LEGEND_LEAGUE_ID`.
+    /// * `season_id` - The id of the season, e.g. `2023-05`.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `PlayerRanking`, or a `CoCClientError` if a page request fails.
+    pub fn stream_league_season_rankings(
+        &self,
+        league_id: i64,
+        season_id: &str,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<PlayerRanking, CoCClientError>> {
+        let path = format!("{}/leagues/{}/seasons/{}", self.url(), league_id, season_id);
+
+        self.stream_items(path, Vec::new(), options)
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
+    use crate::leagues::models::LEGEND_LEAGUE_ID;
+    use crate::testing::{MockResponse, MockServer};
 
-    fn set_up_client() -> CoCClient {
-        let bearer_token = std::env::var("BEARER_TOKEN").expect("env var BEARER_TOKEN not set");
-
-        CoCClient::new(bearer_token, None)
+    async fn set_up_client() -> CoCClient {
+        MockServer::start().await.client().unwrap()
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_capital_leagues() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_capital_leagues(None).await {
             Ok(_) => {
@@ -173,13 +312,10 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_capital_leagues_with_params() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
-        let mut params: HashMap<&str, &str> = HashMap::new();
-
-        params.insert("limit", "5");
+        let params = PageParams::new().limit(5);
 
         match client.get_capital_leagues(Some(params)).await {
             Ok(_) => {
@@ -193,9 +329,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_leagues() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_leagues(None).await {
             Ok(_) => {
@@ -209,13 +344,10 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_leagues_with_params() {
-        let client = set_up_client();
-
-        let mut params: HashMap<&str, &str> = HashMap::new();
+        let client = set_up_client().await;
 
-        params.insert("limit", "5");
+        let params = PageParams::new().limit(5);
 
         match client.get_leagues(Some(params)).await {
             Ok(_) => {
@@ -229,9 +361,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_capital_league_info() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         let league_id = 85000022;
 
@@ -247,9 +378,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_builder_base_leagues() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_builder_base_leagues(None).await {
             Ok(_) => {
@@ -263,13 +393,10 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_builder_base_leagues_with_params() {
-        let client = set_up_client();
-
-        let mut params: HashMap<&str, &str> = HashMap::new();
+        let client = set_up_client().await;
 
-        params.insert("limit", "5");
+        let params = PageParams::new().limit(5);
 
         match client.get_builder_base_leagues(Some(params)).await {
             Ok(_) => {
@@ -283,9 +410,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_builder_base_league_info() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         let league_id = 44000004;
 
@@ -301,9 +427,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_league_info() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         let league_id = 29000000;
 
@@ -319,9 +444,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_war_leagues() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_war_leagues(None).await {
             Ok(_) => {
@@ -335,9 +459,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_war_league_info() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         let league_id = 48000005;
 
@@ -353,9 +476,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_league_seasons() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         let league_id = 29000022;
 
@@ -371,17 +493,14 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_league_season_rankings() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         let league_id = 29000022;
 
         let season_id = "2023-05";
 
-        let mut params: HashMap<&str, &str> = HashMap::new();
-
-        params.insert("limit", "5");
+        let params = PageParams::new().limit(5);
 
         match client
             .get_league_season_rankings(league_id, season_id, Some(params))
@@ -396,4 +515,89 @@ mod tests {
             }
         };
     }
+
+    #[tokio::test]
+    async fn test_league_endpoints_request_documented_paths() {
+        let server = MockServer::start_with_handler(|request| {
+            let ends_with_id = request
+                .path
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .parse::<i64>()
+                .is_ok();
+            let body = if ends_with_id {
+                r#"{"id":29000022,"name":"Legend League"}"#
+            } else {
+                r#"{"items":[],"paging":{"cursors":{}}}"#
+            };
+
+            MockResponse::json(200, body)
+        })
+        .await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        client.get_leagues(None).await.unwrap();
+        client.get_league_info(LEGEND_LEAGUE_ID).await.unwrap();
+        client
+            .get_league_seasons(LEGEND_LEAGUE_ID, None)
+            .await
+            .unwrap();
+        client
+            .get_league_season_rankings(LEGEND_LEAGUE_ID, "2023-05", None)
+            .await
+            .unwrap();
+        client.get_war_leagues(None).await.unwrap();
+        client.get_war_league_info(48000005).await.unwrap();
+        client.get_builder_base_leagues(None).await.unwrap();
+        client.get_builder_base_league_info(44000004).await.unwrap();
+        client.get_capital_leagues(None).await.unwrap();
+        client.get_capital_league_info(85000022).await.unwrap();
+
+        let paths: Vec<String> = server
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "/v1/leagues",
+                "/v1/leagues/29000022",
+                "/v1/leagues/29000022/seasons",
+                "/v1/leagues/29000022/seasons/2023-05",
+                "/v1/warleagues",
+                "/v1/warleagues/48000005",
+                "/v1/builderbaseleagues",
+                "/v1/builderbaseleagues/44000004",
+                "/v1/capitalleagues",
+                "/v1/capitalleagues/85000022",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_league_ids_deserialize_beyond_u32() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                200,
+                r#"{"items":[{"id":29000022,"name":"Legend League","iconUrls":{}},{"id":5000000000,"name":"Future League"}],"paging":{"cursors":{}}}"#,
+            )
+        })
+        .await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let leagues = client.get_leagues(None).await.unwrap();
+
+        assert_eq!(leagues.items[0].id, LEGEND_LEAGUE_ID);
+        assert_eq!(leagues.items[1].id, 5000000000);
+        assert!(leagues.items[1].icon_urls.is_none());
+    }
 }
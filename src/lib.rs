@@ -37,6 +37,15 @@
 //! about a clan, and handle the result.
 //!
 
+/// The `builder` module provides `CoCClientBuilder` for configuring a client.
+/// It allows you to change the base URL, API version, timeouts, user agent, default headers and proxy of the client.
+pub mod builder;
+
+/// The `cache` module provides the in-memory and on-disk caches of API responses.
+/// Responses are kept for as long as their `Cache-Control: max-age` allows, with least recently used entries evicted first.
+/// The on-disk cache survives restarts and serves expired responses while the API is unavailable.
+pub mod cache;
+
 /// The `clans` module handles requests towards the clan endpoints and provides clan models.
 /// It allows you to retrieve information about clans, search for clans, and interact with clan-related data.
 /// This module encapsulates functionality related to clans in the Clash of Clans API client.
@@ -47,14 +56,85 @@ pub mod clans;
 /// The client module allows you to authenticate with a bearer token, send requests, and process responses from the Clash of Clans API.
 pub mod client;
 
+/// The `deserialization` module controls how responses that do not match the models are handled.
+/// Unknown enum values become `Unknown(..)` by default, lenient mode also fills missing fields, and strict mode fails on both.
+pub mod deserialization;
+
+/// The `developer` module provides `KeyManager` for managing API keys through the developer portal.
+/// It logs into the portal, detects the current public IP, and reuses or creates a key bound to it.
+pub mod developer;
+
+/// The `drift` module provides `SchemaDriftDetector`, which compares API responses against the models.
+/// It reports unknown JSON paths and absent expected fields per endpoint, to tell when the crate needs updating.
+pub mod drift;
+
 /// The `errors` module contains all error structs and enums.
 /// It provides a set of error types that can be used throughout the crate to handle and propagate errors consistently.
 /// By utilizing these error types, you can handle various error scenarios that may arise during API interactions or other operations.
 pub mod errors;
 
+/// The `pagination` module walks cursor-paginated endpoints as asynchronous streams.
+/// It follows the `after` cursors returned by the API until the listing is exhausted or a configured cap is hit.
+/// Streams can be resumed from a previously saved cursor.
+pub mod pagination;
+
+/// The `params` module provides typed query parameters for the list endpoints.
+/// Parameters are validated before any request is sent, so mistakes surface as errors instead of being ignored by the API.
+pub mod params;
+
+/// The `rate_limit` module provides the client-side token bucket limiting the requests sent with each API key.
+/// The limiter slows a key down when the API throttles it, and speeds it back up as requests succeed.
+pub mod rate_limit;
+
+/// The `response` module provides `ApiResponse`, a deserialized body together with the metadata of the response.
+/// It exposes the status code, headers, `Cache-Control` expiry and latency, so pollers can schedule their next fetch.
+pub mod response;
+
+/// The `retry` module provides `RetryPolicy` for retrying transient failures with exponential back-off and jitter.
+/// Bad requests, forbidden and not found responses are never retried.
+pub mod retry;
+
+/// The `tag` module provides `Tag`, a validated and normalized player, clan or war tag.
+/// Tags can be converted to and from the numeric account ids they encode.
+pub mod tag;
+
+/// The `timestamp` module provides `Timestamp`, the type the API's timestamps are deserialized into.
+/// It is `time::OffsetDateTime` by default, or `chrono::DateTime<Utc>` with the `chrono` feature.
+pub mod timestamp;
+
+/// The `tokens` module provides `TokenPool` for distributing requests across several API keys.
+/// Keys that get throttled or rejected for their IP address are benched for a while, and per-key usage counters are kept.
+pub mod tokens;
+
+/// The `vcr` module provides `Cassette` for recording requests sent to the API and replaying them without network access.
+/// Recorded cassettes never contain the bearer token, so they can be committed alongside the tests that replay them.
+pub mod vcr;
+
 /// The `players` module handles requests towards the player endpoints and provides player models.
 /// It enables you to retrieve player information, search for players, and perform operations related to player data in the Clash of Clans API.
 /// This module encapsulates functionality related to players in the Clash of Clans API client.
 pub mod players;
+
+/// The `leagues` module handles requests towards the league endpoints and provides league models.
+/// It allows you to list the home village, war, builder base and clan capital leagues, and retrieve Legend League season rankings.
 pub mod leagues;
+
+/// The `labels` module handles requests towards the label endpoints and provides label models.
+/// It allows you to retrieve the clan and player label catalogs, and maps label ids to typed enums.
+pub mod labels;
+
+/// The `locations` module handles requests towards the location endpoints and provides location and ranking models.
+/// It allows you to list regions and countries, and retrieve the clan, player and clan capital rankings of each location.
+pub mod locations;
+
 pub mod goldpass;
+
+/// The `fixtures` module provides golden JSON payloads of every endpoint, shaped like real API responses.
+/// They are checked against the models in the crate's tests, and served by the `testing` mock server.
+#[cfg(any(test, feature = "testing"))]
+pub mod fixtures;
+
+/// The `testing` module provides `MockServer`, a local stand-in for the API serving the `fixtures` or configured responses.
+/// Together with `CoCClientBuilder::base_url` it lets bots exercise every client method offline, including error responses and latency.
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clans::models::BuilderBaseLague;
+use crate::leagues::models::PlayerRankingClan;
+use crate::tag::Tag;
+
+/// The id of the location covering the whole world, used for the global rankings.
+pub const GLOBAL_LOCATION_ID: i64 = 32000006;
+
+/// Represents the location of a Clash of Clans clan or player.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub localized_name: Option<String>,
+    pub id: i64,
+    pub name: String,
+    pub is_country: bool,
+    pub country_code: Option<String>,
+}
+
+/// Represents a clan in the clan rankings of a location.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClanRanking {
+    pub clan_level: i64,
+    pub clan_points: i64,
+    pub location: Option<Location>,
+    pub members: i64,
+    pub tag: Tag,
+    pub name: String,
+    pub rank: i64,
+    pub previous_rank: Option<i64>,
+    pub badge_urls: HashMap<String, String>,
+}
+
+/// Represents a clan in the builder base clan rankings of a location.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClanBuilderBaseRanking {
+    pub clan_level: i64,
+    pub clan_builder_base_points: i64,
+    pub location: Option<Location>,
+    pub members: i64,
+    pub tag: Tag,
+    pub name: String,
+    pub rank: i64,
+    pub previous_rank: Option<i64>,
+    pub badge_urls: HashMap<String, String>,
+}
+
+/// Represents a clan in the clan capital rankings of a location.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClanCapitalRanking {
+    pub clan_level: i64,
+    pub clan_capital_points: i64,
+    pub location: Option<Location>,
+    pub members: i64,
+    pub tag: Tag,
+    pub name: String,
+    pub rank: i64,
+    pub previous_rank: Option<i64>,
+    pub badge_urls: HashMap<String, String>,
+}
+
+/// Represents a player in the builder base player rankings of a location.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerBuilderBaseRanking {
+    pub clan: Option<PlayerRankingClan>,
+    pub builder_base_league: Option<BuilderBaseLague>,
+    pub tag: Tag,
+    pub name: String,
+    pub exp_level: i64,
+    pub rank: i64,
+    pub previous_rank: Option<i64>,
+    pub builder_base_trophies: i64,
+}
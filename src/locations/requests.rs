@@ -0,0 +1,509 @@
+use futures::stream::BoxStream;
+
+use crate::{
+    client::{CoCClient, PagedResponse},
+    errors::CoCClientError,
+    leagues::models::PlayerRanking,
+    pagination::PaginationOptions,
+    params::{PageParams, QueryParams},
+};
+
+use super::models::{
+    ClanBuilderBaseRanking, ClanCapitalRanking, ClanRanking, Location, PlayerBuilderBaseRanking,
+};
+
+impl CoCClient {
+    /// Retrieves the locations, both regions and countries.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the locations as `PagedResponse<Location>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_locations(
+        &self,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<Location>, CoCClientError> {
+        let path = format!("{}/locations", self.url());
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves information about the specified location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the location as `Location` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_location(&self, location_id: i64) -> Result<Location, CoCClientError> {
+        let path = format!("{}/locations/{}", self.url(), location_id);
+
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves the clan rankings of the specified location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the rankings as `PagedResponse<ClanRanking>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_location_clan_rankings(
+        &self,
+        location_id: i64,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<ClanRanking>, CoCClientError> {
+        let path = format!("{}/locations/{}/rankings/clans", self.url(), location_id);
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves the player rankings of the specified location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the rankings as `PagedResponse<PlayerRanking>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_location_player_rankings(
+        &self,
+        location_id: i64,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<PlayerRanking>, CoCClientError> {
+        let path = format!("{}/locations/{}/rankings/players", self.url(), location_id);
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves the builder base player rankings of the specified location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the rankings as `PagedResponse<PlayerBuilderBaseRanking>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_location_builder_base_player_rankings(
+        &self,
+        location_id: i64,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<PlayerBuilderBaseRanking>, CoCClientError> {
+        let path = format!(
+            "{}/locations/{}/rankings/players-builder-base",
+            self.url(),
+            location_id
+        );
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves the builder base clan rankings of the specified location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the rankings as `PagedResponse<ClanBuilderBaseRanking>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_location_builder_base_clan_rankings(
+        &self,
+        location_id: i64,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<ClanBuilderBaseRanking>, CoCClientError> {
+        let path = format!(
+            "{}/locations/{}/rankings/clans-builder-base",
+            self.url(),
+            location_id
+        );
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves the clan capital rankings of the specified location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `params` - Optional paging parameters (limit, before, after).
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the rankings as `PagedResponse<ClanCapitalRanking>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_location_capital_rankings(
+        &self,
+        location_id: i64,
+        params: Option<PageParams>,
+    ) -> Result<PagedResponse<ClanCapitalRanking>, CoCClientError> {
+        let path = format!("{}/locations/{}/rankings/capitals", self.url(), location_id);
+
+        let query = params.unwrap_or_default().to_query()?;
+
+        let client_response = match self.send_get_request_with_query(&path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Streams all locations, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `Location`, or a `CoCClientError` if a page request fails.
+    pub fn stream_locations(
+        &self,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<Location, CoCClientError>> {
+        let path = format!("{}/locations", self.url());
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams the full clan rankings of the specified location, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `ClanRanking`, or a `CoCClientError` if a page request fails.
+    pub fn stream_location_clan_rankings(
+        &self,
+        location_id: i64,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<ClanRanking, CoCClientError>> {
+        let path = format!("{}/locations/{}/rankings/clans", self.url(), location_id);
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams the full player rankings of the specified location, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `PlayerRanking`, or a `CoCClientError` if a page request fails.
+    pub fn stream_location_player_rankings(
+        &self,
+        location_id: i64,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<PlayerRanking, CoCClientError>> {
+        let path = format!("{}/locations/{}/rankings/players", self.url(), location_id);
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams the full builder base player rankings of the specified location, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `PlayerBuilderBaseRanking`, or a `CoCClientError` if a page request fails.
+    pub fn stream_location_builder_base_player_rankings(
+        &self,
+        location_id: i64,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<PlayerBuilderBaseRanking, CoCClientError>> {
+        let path = format!(
+            "{}/locations/{}/rankings/players-builder-base",
+            self.url(),
+            location_id
+        );
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams the full builder base clan rankings of the specified location, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `ClanBuilderBaseRanking`, or a `CoCClientError` if a page request fails.
+    pub fn stream_location_builder_base_clan_rankings(
+        &self,
+        location_id: i64,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<ClanBuilderBaseRanking, CoCClientError>> {
+        let path = format!(
+            "{}/locations/{}/rankings/clans-builder-base",
+            self.url(),
+            location_id
+        );
+
+        self.stream_items(path, Vec::new(), options)
+    }
+
+    /// Streams the full clan capital rankings of the specified location, following the page cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `location_id` - The id of the location, e.g. `GLOBAL_LOCATION_ID`.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `ClanCapitalRanking`, or a `CoCClientError` if a page request fails.
+    pub fn stream_location_capital_rankings(
+        &self,
+        location_id: i64,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<ClanCapitalRanking, CoCClientError>> {
+        let path = format!("{}/locations/{}/rankings/capitals", self.url(), location_id);
+
+        self.stream_items(path, Vec::new(), options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::locations::models::GLOBAL_LOCATION_ID;
+    use crate::testing::{MockResponse, MockServer};
+
+    const BADGE_URLS: &str = r#"{"small":"https://api-assets.clashofclans.com/badges/70/a.png"}"#;
+
+    async fn set_up_client() -> CoCClient {
+        MockServer::start().await.client().unwrap()
+    }
+
+    fn set_up_offline_client(server: &MockServer) -> CoCClient {
+        CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap()
+    }
+
+    fn page(items: &str) -> String {
+        format!(r#"{{"items":[{}],"paging":{{"cursors":{{}}}}}}"#, items)
+    }
+
+    #[tokio::test]
+    async fn test_get_locations() {
+        let client = set_up_client().await;
+
+        if let Err(err) = client.get_locations(None).await {
+            panic!("{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_location_clan_rankings() {
+        let client = set_up_client().await;
+
+        let params = PageParams::new().limit(10);
+        if let Err(err) = client
+            .get_location_clan_rankings(GLOBAL_LOCATION_ID, Some(params))
+            .await
+        {
+            panic!("{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_location() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                200,
+                r#"{"id":32000006,"name":"International","isCountry":false}"#,
+            )
+        })
+        .await;
+        let client = set_up_offline_client(&server);
+
+        let location = client.get_location(GLOBAL_LOCATION_ID).await.unwrap();
+
+        assert_eq!(location.name, "International");
+        assert_eq!(location.country_code, None);
+        assert_eq!(server.requests()[0].path, "/v1/locations/32000006");
+    }
+
+    #[tokio::test]
+    async fn test_location_rankings_request_paths() {
+        let server = MockServer::start_with_handler(|request| {
+            let item = match request.path.rsplit('/').next().unwrap() {
+                "clans" => format!(
+                    r##"{{"clanLevel":20,"clanPoints":50000,"members":50,"tag":"#2LUGVU89Q","name":"A","rank":1,"previousRank":2,"badgeUrls":{}}}"##,
+                    BADGE_URLS
+                ),
+                "players" => String::from(
+                    r##"{"attackWins":10,"defenseWins":2,"tag":"#2288UCQJ","name":"B","expLevel":250,"rank":1,"trophies":6000}"##,
+                ),
+                "players-builder-base" => String::from(
+                    r##"{"builderBaseLeague":{"id":44000036,"name":"Emerald League I"},"tag":"#2288UCQJ","name":"B","expLevel":250,"rank":1,"builderBaseTrophies":6000}"##,
+                ),
+                "clans-builder-base" => format!(
+                    r##"{{"clanLevel":20,"clanBuilderBasePoints":40000,"members":50,"tag":"#2LUGVU89Q","name":"A","rank":1,"badgeUrls":{}}}"##,
+                    BADGE_URLS
+                ),
+                "capitals" => format!(
+                    r##"{{"clanLevel":20,"clanCapitalPoints":4000,"members":50,"tag":"#2LUGVU89Q","name":"A","rank":1,"badgeUrls":{}}}"##,
+                    BADGE_URLS
+                ),
+                _ => return MockResponse::json(404, r#"{"reason":"notFound"}"#),
+            };
+
+            MockResponse::json(200, page(&item))
+        })
+        .await;
+        let client = set_up_offline_client(&server);
+        let params = || Some(PageParams::new().limit(1));
+
+        let clans = client
+            .get_location_clan_rankings(32000008, params())
+            .await
+            .unwrap();
+        assert_eq!(clans.items[0].clan_points, 50000);
+
+        let players = client
+            .get_location_player_rankings(32000008, params())
+            .await
+            .unwrap();
+        assert_eq!(players.items[0].trophies, 6000);
+        assert!(players.items[0].clan.is_none());
+
+        let builder_base_players = client
+            .get_location_builder_base_player_rankings(32000008, params())
+            .await
+            .unwrap();
+        assert_eq!(builder_base_players.items[0].builder_base_trophies, 6000);
+
+        let builder_base_clans = client
+            .get_location_builder_base_clan_rankings(32000008, params())
+            .await
+            .unwrap();
+        assert_eq!(builder_base_clans.items[0].clan_builder_base_points, 40000);
+
+        let capitals = client
+            .get_location_capital_rankings(32000008, params())
+            .await
+            .unwrap();
+        assert_eq!(capitals.items[0].clan_capital_points, 4000);
+
+        let paths: Vec<String> = server
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/v1/locations/32000008/rankings/clans",
+                "/v1/locations/32000008/rankings/players",
+                "/v1/locations/32000008/rankings/players-builder-base",
+                "/v1/locations/32000008/rankings/clans-builder-base",
+                "/v1/locations/32000008/rankings/capitals",
+            ]
+        );
+        assert!(server
+            .requests()
+            .iter()
+            .all(|request| request.query_param("limit") == Some("1")));
+    }
+
+    #[tokio::test]
+    async fn test_stream_locations() {
+        let server = MockServer::start_with_handler(|request| match request.query_param("after") {
+            None => MockResponse::json(
+                200,
+                r#"{"items":[{"id":32000000,"name":"Europe","isCountry":false}],"paging":{"cursors":{"after":"c1"}}}"#,
+            ),
+            Some(_) => MockResponse::json(
+                200,
+                page(r#"{"id":32000007,"name":"Afghanistan","isCountry":true,"countryCode":"AF"}"#),
+            ),
+        })
+        .await;
+        let client = set_up_offline_client(&server);
+
+        let names: Vec<String> = client
+            .stream_locations(PaginationOptions::new())
+            .map(|location| location.unwrap().name)
+            .collect()
+            .await;
+
+        assert_eq!(names, vec!["Europe", "Afghanistan"]);
+    }
+}
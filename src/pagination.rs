@@ -0,0 +1,307 @@
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    client::{CoCClient, PagedResponse},
+    errors::CoCClientError,
+    params::{PageParams, QueryParams},
+};
+
+/// Controls how a paginated endpoint is walked by the `stream_*` methods.
+///
+/// By default every page is fetched until the API stops returning an `after` cursor.
+#[derive(Clone, Debug, Default)]
+pub struct PaginationOptions {
+    /// The number of items requested per page (the `limit` query parameter).
+    pub page_size: Option<u32>,
+    /// Stops the stream after this many items have been yielded.
+    pub max_items: Option<usize>,
+    /// Stops the stream after this many pages have been fetched.
+    pub max_pages: Option<usize>,
+    /// Resumes the listing from a previously saved `after` cursor.
+    pub after: Option<String>,
+}
+
+impl PaginationOptions {
+    /// Creates options that walk the whole listing with the API's default page size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of items requested per page, at least 1.
+    ///
+    /// A page size of 0 makes the stream yield `CoCClientError::InvalidParameters` without
+    /// sending any request.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Caps the total number of items yielded by the stream.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Caps the number of pages fetched by the stream.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Starts the listing after the given cursor, as found in `Paging.cursors.after`.
+    pub fn resume_after(mut self, cursor: impl Into<String>) -> Self {
+        self.after = Some(cursor.into());
+        self
+    }
+}
+
+struct PageState {
+    client: CoCClient,
+    path: String,
+    params: Vec<(String, String)>,
+    page_size: Option<u32>,
+    after: Option<String>,
+    pages_fetched: usize,
+    max_pages: Option<usize>,
+    exhausted: bool,
+}
+
+impl CoCClient {
+    /// Walks a paginated endpoint page by page, following the `after` cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The full URL of the paginated endpoint.
+    /// * `params` - Additional query parameters sent with every page request.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each `PagedResponse` as it is fetched. The stream ends when the
+    /// API returns no `after` cursor, an empty page, or `max_pages` is reached. An error ends
+    /// the stream after being yielded. Invalid options are yielded as
+    /// `CoCClientError::InvalidParameters` before any request is sent. `max_items` is ignored
+    /// by this method.
+    pub fn stream_pages<T>(
+        &self,
+        path: String,
+        params: Vec<(String, String)>,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<PagedResponse<T>, CoCClientError>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let page_params = PageParams {
+            limit: options.page_size,
+            after: options.after.clone(),
+            before: None,
+        };
+
+        if let Err(err) = page_params.to_query() {
+            return stream::once(async move { Err(err) }).boxed();
+        }
+
+        let state = PageState {
+            client: self.clone(),
+            path,
+            params,
+            page_size: options.page_size,
+            after: options.after,
+            pages_fetched: 0,
+            max_pages: options.max_pages,
+            exhausted: false,
+        };
+
+        stream::try_unfold(state, |mut state| async move {
+            let max_pages_reached = state
+                .max_pages
+                .is_some_and(|max_pages| state.pages_fetched >= max_pages);
+
+            if state.exhausted || max_pages_reached {
+                return Ok(None);
+            }
+
+            let page_params = PageParams {
+                limit: state.page_size,
+                after: state.after.clone(),
+                before: None,
+            };
+
+            let mut query = state.params.clone();
+            query.extend(page_params.to_query()?);
+
+            let client_response = state
+                .client
+                .send_get_request_with_query(&state.path, query)
+                .await?;
+
+            let page: PagedResponse<T> = CoCClient::handle_response(client_response).await?;
+
+            state.pages_fetched += 1;
+            state.after = page.next_cursor().map(String::from);
+            state.exhausted = state.after.is_none() || page.items.is_empty();
+
+            Ok(Some((page, state)))
+        })
+        .boxed()
+    }
+
+    /// Walks a paginated endpoint item by item, following the `after` cursors.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The full URL of the paginated endpoint.
+    /// * `params` - Additional query parameters sent with every page request.
+    /// * `options` - Page size, caps and resume cursor for the walk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding every item of every page. Pages are only fetched once the
+    /// items of the previous page have been consumed, so `max_items` also limits the requests made.
+    pub fn stream_items<T>(
+        &self,
+        path: String,
+        params: Vec<(String, String)>,
+        options: PaginationOptions,
+    ) -> BoxStream<'static, Result<T, CoCClientError>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let max_items = options.max_items.unwrap_or(usize::MAX);
+
+        self.stream_pages(path, params, options)
+            .map_ok(|page: PagedResponse<T>| stream::iter(page.items.into_iter().map(Ok)))
+            .try_flatten()
+            .take(max_items)
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::testing::{MockResponse, MockServer};
+
+    async fn set_up_server() -> MockServer {
+        MockServer::start_with_handler(|request| {
+            let body = match request.query_param("after") {
+                None => r#"{"items":[1,2],"paging":{"cursors":{"after":"c1"}}}"#,
+                Some("c1") => {
+                    r#"{"items":[3,4],"paging":{"cursors":{"before":"c1","after":"c2"}}}"#
+                }
+                Some(_) => r#"{"items":[5],"paging":{"cursors":{"before":"c2"}}}"#,
+            };
+            MockResponse::json(200, body)
+        })
+        .await
+    }
+
+    fn set_up_client(server: &MockServer) -> CoCClient {
+        CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap()
+    }
+
+    async fn collect(client: CoCClient, options: PaginationOptions) -> Vec<i64> {
+        let path = format!("{}/items", client.url());
+        client
+            .stream_items::<i64>(path, Vec::new(), options)
+            .map(|item| item.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_follows_cursors() {
+        let server = set_up_server().await;
+        let client = set_up_client(&server);
+
+        let items = collect(client, PaginationOptions::new().page_size(2)).await;
+
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|r| r.method == "GET" && r.path == "/v1/items"));
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Bearer MY_BEARER_TOKEN")
+        );
+        assert!(requests.iter().all(|r| r.query_param("limit") == Some("2")));
+        assert_eq!(requests[2].query_param("after"), Some("c2"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_max_items_stops_fetching() {
+        let server = set_up_server().await;
+        let client = set_up_client(&server);
+
+        let items = collect(client, PaginationOptions::new().max_items(3)).await;
+
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_max_pages() {
+        let server = set_up_server().await;
+        let client = set_up_client(&server);
+
+        let items = collect(client, PaginationOptions::new().max_pages(1)).await;
+
+        assert_eq!(items, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_resume_after_cursor() {
+        let server = set_up_server().await;
+        let client = set_up_client(&server);
+
+        let items = collect(client, PaginationOptions::new().resume_after("c1")).await;
+
+        assert_eq!(items, vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_yields_error() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(404, r#"{"reason":"notFound","message":"Not found"}"#)
+        })
+        .await;
+        let client = set_up_client(&server);
+        let path = format!("{}/items", client.url());
+
+        let items: Vec<Result<i64, CoCClientError>> = client
+            .stream_items(path, Vec::new(), PaginationOptions::new())
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 1);
+        assert!(matches!(items[0], Err(CoCClientError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_rejects_invalid_page_size() {
+        let server = set_up_server().await;
+        let client = set_up_client(&server);
+        let path = format!("{}/items", client.url());
+
+        let items: Vec<Result<i64, CoCClientError>> = client
+            .stream_items(path, Vec::new(), PaginationOptions::new().page_size(0))
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 1);
+        assert!(matches!(
+            items[0],
+            Err(CoCClientError::InvalidParameters(_))
+        ));
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use crate::errors::CoCClientError;
+
+/// Represents a set of parameters that can be serialized into a request's query string.
+pub trait QueryParams {
+    /// Validates the parameters and serializes them into query pairs.
+    ///
+    /// # Returns
+    ///
+    /// Returns the query pairs on success, or a `CoCClientError::InvalidParameters`
+    /// describing the first invalid parameter.
+    fn to_query(&self) -> Result<Vec<(String, String)>, CoCClientError>;
+}
+
+/// Represents the paging parameters shared by every list endpoint.
+///
+/// `after` and `before` are mutually exclusive, and `limit` must be at least 1.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PageParams {
+    /// The maximum number of items returned in the page.
+    pub limit: Option<u32>,
+    /// Returns the items after this cursor.
+    pub after: Option<String>,
+    /// Returns the items before this cursor.
+    pub before: Option<String>,
+}
+
+impl PageParams {
+    /// Creates empty paging parameters, letting the API pick its defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of items returned in the page.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Requests the items after the given cursor.
+    pub fn after(mut self, cursor: impl Into<String>) -> Self {
+        self.after = Some(cursor.into());
+        self
+    }
+
+    /// Requests the items before the given cursor.
+    pub fn before(mut self, cursor: impl Into<String>) -> Self {
+        self.before = Some(cursor.into());
+        self
+    }
+}
+
+impl QueryParams for PageParams {
+    fn to_query(&self) -> Result<Vec<(String, String)>, CoCClientError> {
+        if self.after.is_some() && self.before.is_some() {
+            return Err(CoCClientError::InvalidParameters(String::from(
+                "`after` and `before` cannot be used together",
+            )));
+        }
+
+        let mut query = Vec::new();
+
+        if let Some(limit) = self.limit {
+            if limit == 0 {
+                return Err(CoCClientError::InvalidParameters(String::from(
+                    "`limit` must be at least 1",
+                )));
+            }
+            query.push((String::from("limit"), limit.to_string()));
+        }
+
+        if let Some(after) = &self.after {
+            query.push((String::from("after"), after.clone()));
+        }
+
+        if let Some(before) = &self.before {
+            query.push((String::from("before"), before.clone()));
+        }
+
+        Ok(query)
+    }
+}
+
+/// Raw parameters are passed through unchecked, as an escape hatch for filters the typed
+/// parameters do not cover yet.
+impl QueryParams for HashMap<&str, &str> {
+    fn to_query(&self) -> Result<Vec<(String, String)>, CoCClientError> {
+        Ok(self
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_params_to_query() {
+        let params = PageParams::new().limit(5).after("abc");
+
+        let query = params.to_query().unwrap();
+
+        assert_eq!(
+            query,
+            vec![
+                (String::from("limit"), String::from("5")),
+                (String::from("after"), String::from("abc")),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_page_params_to_query() {
+        assert!(PageParams::new().to_query().unwrap().is_empty());
+    }
+
+    #[test]
+    fn page_params_rejects_after_and_before() {
+        let params = PageParams::new().after("abc").before("def");
+
+        assert!(matches!(
+            params.to_query(),
+            Err(CoCClientError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn page_params_rejects_zero_limit() {
+        let params = PageParams::new().limit(0);
+
+        assert!(matches!(
+            params.to_query(),
+            Err(CoCClientError::InvalidParameters(_))
+        ));
+    }
+}
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::clans::models::{BuilderBaseLague, ClanMemberRole, Label, League, PlayerHouse};
+use crate::deserialization::api_enum;
+use crate::tag::Tag;
 
 /// Represents a player in Clash of Clans.
 #[derive(Serialize, Deserialize, Debug)]
@@ -12,51 +14,109 @@ pub struct Player {
     pub builder_base_league: Option<BuilderBaseLague>,
     pub clan: Option<PlayerClan>,
     pub role: Option<ClanMemberRole>,
-    pub war_preference: WarPreference,
+    pub war_preference: Option<WarPreference>,
     pub attack_wins: i64,
     pub defense_wins: i64,
-    pub versus_trophies: Option<i64>,
     pub town_hall_level: i64,
     pub town_hall_weapon_level: Option<i64>,
-    pub versus_battle_wins: Option<i64>,
     pub legend_statistics: Option<PlayerLegendStatistics>,
     pub troops: Vec<PlayerItemLevel>,
     pub heroes: Vec<PlayerItemLevel>,
+    #[serde(default)]
+    pub hero_equipment: Vec<PlayerItemLevel>,
     pub spells: Vec<PlayerItemLevel>,
     pub labels: Vec<Label>,
-    pub tag: String,
+    pub tag: Tag,
     pub name: String,
     pub exp_level: i64,
     pub trophies: i64,
     pub best_trophies: i64,
     pub donations: i64,
     pub donations_received: i64,
-    pub builder_hall_level: i64,
+    pub builder_hall_level: Option<i64>,
+    #[serde(default)]
     pub builder_base_trophies: i64,
+    #[serde(default)]
     pub best_builder_base_trophies: i64,
     pub war_stars: i64,
-    pub achivements: Option<Vec<PlayerAchivementProgress>>,
+    #[serde(default)]
+    pub achievements: Vec<PlayerAchievementProgress>,
+    #[serde(default)]
     pub clan_capital_contributions: i64,
     pub player_house: Option<PlayerHouse>,
 }
 
+impl Player {
+    /// Returns every item of the player together with its category.
+    ///
+    /// Pets, siege machines and super troops are listed by the API among the troops,
+    /// and are told apart here.
+    pub fn items(&self) -> Vec<(PlayerItemCategory, &PlayerItemLevel)> {
+        let troops = self
+            .troops
+            .iter()
+            .map(|troop| (PlayerItemCategory::of_troop(troop), troop));
+        let spells = self
+            .spells
+            .iter()
+            .map(|spell| (PlayerItemCategory::Spell, spell));
+        let heroes = self
+            .heroes
+            .iter()
+            .map(|hero| (PlayerItemCategory::Hero, hero));
+        let equipment = self
+            .hero_equipment
+            .iter()
+            .map(|equipment| (PlayerItemCategory::Equipment, equipment));
+
+        troops
+            .chain(spells)
+            .chain(heroes)
+            .chain(equipment)
+            .collect()
+    }
+
+    /// Returns the items of the player in the given category.
+    pub fn items_in(&self, category: PlayerItemCategory) -> Vec<&PlayerItemLevel> {
+        self.items()
+            .into_iter()
+            .filter(|(item_category, _)| *item_category == category)
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    /// Returns the hero pets of the player.
+    pub fn pets(&self) -> Vec<&PlayerItemLevel> {
+        self.items_in(PlayerItemCategory::Pet)
+    }
+
+    /// Returns the siege machines of the player.
+    pub fn siege_machines(&self) -> Vec<&PlayerItemLevel> {
+        self.items_in(PlayerItemCategory::SiegeMachine)
+    }
+
+    /// Returns the super troops of the player.
+    pub fn super_troops(&self) -> Vec<&PlayerItemLevel> {
+        self.items_in(PlayerItemCategory::SuperTroop)
+    }
+}
+
 /// Represents the clan information of a player.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayerClan {
-    pub tag: String,
+    pub tag: Tag,
     pub clan_level: i64,
     pub name: String,
     pub badge_urls: HashMap<String, String>,
 }
 
-/// Represents the war preference of a player.
-#[derive(Serialize, Deserialize, Debug)]
-pub enum WarPreference {
-    #[serde(rename = "out")]
-    Out,
-    #[serde(rename = "in")]
-    In,
+api_enum! {
+    /// Represents the war preference of a player.
+    pub enum WarPreference {
+        Out => "out",
+        In => "in",
+    }
 }
 
 /// Represents the legend statistics of a player.
@@ -65,12 +125,10 @@ pub enum WarPreference {
 pub struct PlayerLegendStatistics {
     pub legend_trophies: i64,
     pub previous_builder_base_season: Option<LegendLeagueTournamentSeasonResult>,
-    pub previous_versus_season: Option<LegendLeagueTournamentSeasonResult>,
     pub best_builder_base_season: Option<LegendLeagueTournamentSeasonResult>,
-    pub best_versus_season: Option<LegendLeagueTournamentSeasonResult>,
     pub previous_season: Option<LegendLeagueTournamentSeasonResult>,
     pub best_season: Option<LegendLeagueTournamentSeasonResult>,
-    pub current_seasob: Option<LegendLeagueTournamentSeasonResult>,
+    pub current_season: Option<LegendLeagueTournamentSeasonResult>,
 }
 
 /// Represents the result of a legend league tournament season for a player.
@@ -78,10 +136,10 @@ pub struct PlayerLegendStatistics {
 pub struct LegendLeagueTournamentSeasonResult {
     pub trophies: i64,
     pub id: Option<String>,
-    pub rank: i64,
+    pub rank: Option<i64>,
 }
 
-/// Represents a level of a player's item (troop, hero, or spell).
+/// Represents a level of a player's item (troop, pet, siege machine, spell, hero or hero equipment).
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayerItemLevel {
@@ -90,26 +148,149 @@ pub struct PlayerItemLevel {
     pub max_level: i64,
     pub village: VillageType,
     pub super_troop_is_active: Option<bool>,
+    /// The equipment currently worn, only set on heroes.
+    pub equipment: Option<Vec<PlayerItemLevel>>,
 }
 
-/// Represents the type of village (home or builder base).
-#[derive(Serialize, Deserialize, Debug)]
-pub enum VillageType {
-    #[serde(rename = "home")]
-    HomeVillage,
-    #[serde(rename = "builderBase")]
-    BuilderBase,
+/// The names of the hero pets, which the API lists among the troops.
+pub const PET_NAMES: &[&str] = &[
+    "L.A.S.S.I",
+    "Electro Owl",
+    "Mighty Yak",
+    "Unicorn",
+    "Frosty",
+    "Diggy",
+    "Poison Lizard",
+    "Phoenix",
+    "Spirit Fox",
+    "Angry Jelly",
+    "Sneezy",
+    "Greedy Raven",
+];
+
+/// The names of the siege machines, which the API lists among the troops.
+pub const SIEGE_MACHINE_NAMES: &[&str] = &[
+    "Wall Wrecker",
+    "Battle Blimp",
+    "Stone Slammer",
+    "Siege Barracks",
+    "Log Launcher",
+    "Flame Flinger",
+    "Battle Drill",
+    "Troop Launcher",
+];
+
+/// The names of the super troops, which the API lists among the troops.
+pub const SUPER_TROOP_NAMES: &[&str] = &[
+    "Super Barbarian",
+    "Super Archer",
+    "Super Giant",
+    "Sneaky Goblin",
+    "Super Wall Breaker",
+    "Rocket Balloon",
+    "Super Wizard",
+    "Super Dragon",
+    "Inferno Dragon",
+    "Super Minion",
+    "Super Valkyrie",
+    "Super Witch",
+    "Ice Hound",
+    "Super Bowler",
+    "Super Miner",
+    "Super Hog Rider",
+    "Super Yeti",
+];
+
+/// Represents the category of a player's item.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PlayerItemCategory {
+    Troop,
+    Pet,
+    SiegeMachine,
+    SuperTroop,
+    Spell,
+    Hero,
+    Equipment,
+}
+
+impl PlayerItemCategory {
+    /// Categorizes an item from the `troops` list of a player.
+    ///
+    /// Builder base troops are always troops. Home village items are told apart by name,
+    /// and by `superTroopIsActive` for super troops the crate does not know yet.
+    pub fn of_troop(troop: &PlayerItemLevel) -> Self {
+        if troop.village == VillageType::BuilderBase {
+            return PlayerItemCategory::Troop;
+        }
+
+        let name = troop.name.as_str();
+
+        if PET_NAMES.contains(&name) {
+            PlayerItemCategory::Pet
+        } else if SIEGE_MACHINE_NAMES.contains(&name) {
+            PlayerItemCategory::SiegeMachine
+        } else if SUPER_TROOP_NAMES.contains(&name) || troop.super_troop_is_active.is_some() {
+            PlayerItemCategory::SuperTroop
+        } else {
+            PlayerItemCategory::Troop
+        }
+    }
+}
+
+api_enum! {
+    /// Represents the type of village (home, builder base or clan capital).
+    pub enum VillageType {
+        HomeVillage => "home",
+        BuilderBase => "builderBase",
+        ClanCapital => "clanCapital",
+    }
 }
 
 /// Represents the progress of a player's achievement.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct PlayerAchivementProgress {
+pub struct PlayerAchievementProgress {
     pub stars: i64,
     pub value: i64,
     pub name: String,
     pub target: i64,
     pub info: String,
-    pub completion_info: String,
+    pub completion_info: Option<String>,
     pub village: VillageType,
 }
+
+/// Represents the progress of a player's achievement.
+#[deprecated(
+    since = "0.8.0",
+    note = "use `PlayerAchievementProgress`, spelled like the API's `achievements`"
+)]
+pub type PlayerAchivementProgress = PlayerAchievementProgress;
+
+/// Represents the body of a request to verify a player's API token.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyTokenRequest {
+    pub token: String,
+}
+
+/// Represents the result of verifying a player's API token.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyTokenResponse {
+    pub tag: Tag,
+    pub token: String,
+    pub status: VerifyTokenStatus,
+}
+
+impl VerifyTokenResponse {
+    /// Returns whether the token belongs to the player.
+    pub fn is_valid(&self) -> bool {
+        self.status == VerifyTokenStatus::Ok
+    }
+}
+
+api_enum! {
+    /// Represents the status of a verified player API token.
+    pub enum VerifyTokenStatus {
+        Ok => "ok",
+        Invalid => "invalid",
+    }
+}
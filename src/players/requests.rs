@@ -1,9 +1,10 @@
 use crate::errors::CoCClientError;
-use urlencoding::encode;
 
 use crate::client::CoCClient;
+use crate::response::ApiResponse;
+use crate::tag::IntoTag;
 
-use super::models::Player;
+use super::models::{Player, VerifyTokenRequest, VerifyTokenResponse};
 
 impl CoCClient {
     /// Retrieves player information for the specified player tag.
@@ -16,12 +17,68 @@ impl CoCClient {
     ///
     /// Returns a `Result` containing the player information as `Player` on success,
     /// or a `CoCClientError` if there was an error in the request or response.
-    pub async fn get_player_info(self: Self, player_tag: &str) -> Result<Player, CoCClientError> {
-        let encoded_player_tag = encode(&player_tag).into_owned();
+    pub async fn get_player_info(
+        &self,
+        player_tag: impl IntoTag,
+    ) -> Result<Player, CoCClientError> {
+        let encoded_player_tag = player_tag.into_tag()?.encoded();
 
-        let path = format!("{}/players/{}", self.url, encoded_player_tag);
+        let path = format!("{}/players/{}", self.url(), encoded_player_tag);
 
-        let client_response = match self.send_get_request(&path, None).await {
+        let client_response = match self.send_get_request_with_query(&path, Vec::new()).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        CoCClient::handle_response(client_response).await
+    }
+
+    /// Retrieves player information for the specified player tag, together with the response metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_tag` - The tag of the player for which to retrieve information.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the player information as `ApiResponse<Player>` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_player_info_response(
+        &self,
+        player_tag: impl IntoTag,
+    ) -> Result<ApiResponse<Player>, CoCClientError> {
+        let encoded_player_tag = player_tag.into_tag()?.encoded();
+
+        let path = format!("{}/players/{}", self.url(), encoded_player_tag);
+
+        self.get_api_response(&path, Vec::new()).await
+    }
+
+    /// Verifies that an API token, shown in the in-game settings, belongs to the specified player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_tag` - The tag of the player whose token should be verified.
+    /// * `token` - The API token provided by the player.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the verification result as `VerifyTokenResponse` on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn verify_player_token(
+        &self,
+        player_tag: impl IntoTag,
+        token: &str,
+    ) -> Result<VerifyTokenResponse, CoCClientError> {
+        let encoded_player_tag = player_tag.into_tag()?.encoded();
+
+        let path = format!("{}/players/{}/verifytoken", self.url(), encoded_player_tag);
+
+        let body = VerifyTokenRequest {
+            token: token.to_string(),
+        };
+
+        let client_response = match self.send_post_request(&path, &body).await {
             Ok(client_response) => client_response,
             Err(err) => return Err(err),
         };
@@ -31,21 +88,22 @@ impl CoCClient {
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
+    use crate::deserialization::DeserializationMode;
+    use crate::players::models::{PlayerItemCategory, VerifyTokenStatus};
+    use crate::testing::{MockResponse, MockServer};
 
     static PLAYER_TAG: &str = "#2288UCQJ";
 
-    fn set_up_client() -> CoCClient {
-        let bearer_token = std::env::var("BEARER_TOKEN").expect("env var BEARER_TOKEN not set");
-
-        CoCClient::new(bearer_token, None)
+    async fn set_up_client() -> CoCClient {
+        MockServer::start().await.client().unwrap()
     }
 
     #[tokio::test]
-    #[ignore = "Bearer token is not configured for GitHub Actions IP address"]
     async fn test_get_player_info() {
-        let client = set_up_client();
+        let client = set_up_client().await;
 
         match client.get_player_info(PLAYER_TAG).await {
             Ok(_) => {
@@ -57,4 +115,201 @@ mod tests {
             }
         };
     }
+
+    #[tokio::test]
+    async fn test_get_player_info_normalizes_tag() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(404, r#"{"reason":"notFound","message":"Not found"}"#)
+        })
+        .await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let result = client.get_player_info("2288ucqj").await;
+
+        assert!(matches!(result, Err(CoCClientError::NotFound(_))));
+        assert_eq!(server.requests()[0].path, "/v1/players/%232288UCQJ");
+    }
+
+    #[tokio::test]
+    async fn test_get_player_info_deserializes_current_payload() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                200,
+                r##"{
+                    "tag": "#2288UCQJ", "name": "Chief", "townHallLevel": 16, "expLevel": 250,
+                    "trophies": 5000, "bestTrophies": 6000, "warStars": 1500,
+                    "attackWins": 50, "defenseWins": 3, "builderHallLevel": 10,
+                    "builderBaseTrophies": 4000, "bestBuilderBaseTrophies": 4200,
+                    "warPreference": "in", "donations": 100, "donationsReceived": 80,
+                    "clanCapitalContributions": 123456,
+                    "builderBaseLeague": {"id": 44000036, "name": "Emerald League I"},
+                    "legendStatistics": {
+                        "legendTrophies": 5000,
+                        "currentSeason": {"trophies": 5300},
+                        "bestSeason": {"id": "2023-05", "rank": 10, "trophies": 6000}
+                    },
+                    "achievements": [
+                        {"name": "Bigger Coffers", "stars": 3, "value": 16, "target": 10,
+                         "info": "Upgrade a Gold Storage to level 10", "completionInfo": null,
+                         "village": "home"}
+                    ],
+                    "labels": [],
+                    "troops": [
+                        {"name": "Barbarian", "level": 11, "maxLevel": 12, "village": "home"},
+                        {"name": "Super Barbarian", "level": 1, "maxLevel": 1, "village": "home", "superTroopIsActive": true},
+                        {"name": "Wall Wrecker", "level": 4, "maxLevel": 5, "village": "home"},
+                        {"name": "Electro Owl", "level": 10, "maxLevel": 10, "village": "home"},
+                        {"name": "Raged Barbarian", "level": 18, "maxLevel": 20, "village": "builderBase"}
+                    ],
+                    "heroes": [
+                        {"name": "Barbarian King", "level": 90, "maxLevel": 95, "village": "home",
+                         "equipment": [{"name": "Earthquake Boots", "level": 18, "maxLevel": 18, "village": "home"}]}
+                    ],
+                    "heroEquipment": [
+                        {"name": "Earthquake Boots", "level": 18, "maxLevel": 18, "village": "home"},
+                        {"name": "Giant Gauntlet", "level": 27, "maxLevel": 27, "village": "home"}
+                    ],
+                    "spells": [{"name": "Lightning Spell", "level": 11, "maxLevel": 11, "village": "home"}]
+                }"##,
+            )
+        })
+        .await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let player = client.get_player_info(PLAYER_TAG).await.unwrap();
+
+        assert_eq!(player.clan_capital_contributions, 123456);
+        assert_eq!(player.builder_base_league.as_ref().unwrap().id, 44000036);
+        assert_eq!(player.achievements[0].name, "Bigger Coffers");
+        assert_eq!(player.hero_equipment.len(), 2);
+        assert_eq!(
+            player.heroes[0].equipment.as_ref().unwrap()[0].name,
+            "Earthquake Boots"
+        );
+        assert_eq!(player.pets()[0].name, "Electro Owl");
+        assert_eq!(player.siege_machines()[0].name, "Wall Wrecker");
+        assert_eq!(player.super_troops()[0].name, "Super Barbarian");
+
+        let categories: Vec<PlayerItemCategory> = player
+            .items()
+            .into_iter()
+            .map(|(category, _)| category)
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                PlayerItemCategory::Troop,
+                PlayerItemCategory::SuperTroop,
+                PlayerItemCategory::SiegeMachine,
+                PlayerItemCategory::Pet,
+                PlayerItemCategory::Troop,
+                PlayerItemCategory::Spell,
+                PlayerItemCategory::Hero,
+                PlayerItemCategory::Equipment,
+                PlayerItemCategory::Equipment,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_player_token_posts_token() {
+        let server = MockServer::start_with_handler(|request| {
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            let status = if body["token"] == "abc123" {
+                "ok"
+            } else {
+                "invalid"
+            };
+
+            MockResponse::json(
+                200,
+                serde_json::json!({"tag": "#2288UCQJ", "token": body["token"], "status": status})
+                    .to_string(),
+            )
+        })
+        .await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .response_cache(16)
+            .build()
+            .unwrap();
+
+        let valid = client
+            .verify_player_token(PLAYER_TAG, "abc123")
+            .await
+            .unwrap();
+        let invalid = client
+            .verify_player_token(PLAYER_TAG, "nope")
+            .await
+            .unwrap();
+
+        assert!(valid.is_valid());
+        assert_eq!(valid.tag, PLAYER_TAG);
+        assert!(!invalid.is_valid());
+        assert_eq!(invalid.token, "nope");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/v1/players/%232288UCQJ/verifytoken");
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Bearer MY_BEARER_TOKEN")
+        );
+        assert_eq!(requests[0].body, r#"{"token":"abc123"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_deserialization_mode_controls_unknown_values() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                200,
+                r##"{"tag":"#2288UCQJ","token":"abc123","status":"expired"}"##,
+            )
+        })
+        .await;
+        let standard = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+        let strict = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .deserialization_mode(DeserializationMode::Strict)
+            .build()
+            .unwrap();
+
+        let response = standard
+            .verify_player_token(PLAYER_TAG, "abc123")
+            .await
+            .unwrap();
+        let result = strict.verify_player_token(PLAYER_TAG, "abc123").await;
+
+        assert_eq!(
+            response.status,
+            VerifyTokenStatus::Unknown(String::from("expired"))
+        );
+        assert!(!response.is_valid());
+        assert!(matches!(result, Err(CoCClientError::Deserlisation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_player_info_rejects_invalid_tag() {
+        let server = MockServer::start_with_handler(|_| MockResponse::json(200, "{}")).await;
+        let client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let result = client.get_player_info("#NOT-A-TAG").await;
+
+        assert!(matches!(result, Err(CoCClientError::InvalidParameters(_))));
+        assert!(server.requests().is_empty());
+    }
 }
@@ -0,0 +1,263 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Configures the client-side token bucket limiting the requests sent with each API key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// The sustained number of requests per second allowed for each key.
+    pub requests_per_second: f64,
+    /// The number of requests that can be sent at once after a quiet period.
+    pub burst: u32,
+    /// The factor the rate is multiplied by after the API throttles a request.
+    pub backoff_factor: f64,
+    /// The lowest rate the back-off can slow a key down to, in requests per second.
+    pub min_requests_per_second: f64,
+    /// The fraction of `requests_per_second` regained after each successful request.
+    pub recovery_step: f64,
+}
+
+impl RateLimit {
+    /// Allows `requests_per_second` requests per second for each key, with a burst of the same size.
+    pub fn per_second(requests_per_second: f64) -> Self {
+        Self {
+            requests_per_second,
+            burst: requests_per_second.ceil().max(1.0) as u32,
+            backoff_factor: 0.5,
+            min_requests_per_second: requests_per_second / 16.0,
+            recovery_step: 0.05,
+        }
+    }
+
+    /// Sets the number of requests that can be sent at once after a quiet period.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Sets the factor the rate is multiplied by after the API throttles a request.
+    ///
+    /// A factor of `1.0` disables the adaptive back-off.
+    pub fn backoff_factor(mut self, backoff_factor: f64) -> Self {
+        self.backoff_factor = backoff_factor.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the lowest rate the back-off can slow a key down to.
+    pub fn min_requests_per_second(mut self, min_requests_per_second: f64) -> Self {
+        self.min_requests_per_second = min_requests_per_second;
+        self
+    }
+
+    /// Sets the fraction of the configured rate regained after each successful request.
+    pub fn recovery_step(mut self, recovery_step: f64) -> Self {
+        self.recovery_step = recovery_step;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    rate: f64,
+    last_refill: Instant,
+}
+
+/// Represents the token buckets of every key of a client.
+///
+/// Requests reserve a token when they arrive and wait until the bucket has refilled enough,
+/// so concurrent requests are queued in arrival order.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimit,
+    buckets: Mutex<Vec<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter with a full bucket for each of `keys` keys.
+    pub(crate) fn new(config: RateLimit, keys: usize) -> Self {
+        let now = Instant::now();
+
+        let buckets = (0..keys)
+            .map(|_| Bucket {
+                tokens: f64::from(config.burst),
+                rate: config.requests_per_second,
+                last_refill: now,
+            })
+            .collect();
+
+        Self {
+            config,
+            buckets: Mutex::new(buckets),
+        }
+    }
+
+    /// Returns the configuration of the limiter.
+    pub fn config(&self) -> RateLimit {
+        self.config
+    }
+
+    /// Returns the current rate of the key at `key`, in requests per second.
+    ///
+    /// This is lower than the configured rate while the key recovers from being throttled.
+    pub fn current_rate(&self, key: usize) -> Option<f64> {
+        self.buckets
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|bucket| bucket.rate)
+    }
+
+    /// Waits until a request can be sent with the key at `key`.
+    pub(crate) async fn acquire(&self, key: usize) {
+        let wait = {
+            let mut buckets = self.buckets.lock().unwrap();
+            let Some(bucket) = buckets.get_mut(key) else {
+                return;
+            };
+
+            self.refill(bucket);
+
+            bucket.tokens -= 1.0;
+
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+
+            Duration::from_secs_f64(-bucket.tokens / bucket.rate)
+        };
+
+        tokio::time::sleep(wait).await;
+    }
+
+    /// Slows the key down after the API throttled a request sent with it.
+    pub(crate) fn throttled(&self, key: usize) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Some(bucket) = buckets.get_mut(key) else {
+            return;
+        };
+
+        self.refill(bucket);
+
+        bucket.rate = (bucket.rate * self.config.backoff_factor)
+            .max(self.config.min_requests_per_second)
+            .min(self.config.requests_per_second);
+        bucket.tokens = bucket.tokens.min(0.0);
+    }
+
+    /// Speeds the key back up towards the configured rate after a successful request.
+    pub(crate) fn succeeded(&self, key: usize) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Some(bucket) = buckets.get_mut(key) else {
+            return;
+        };
+
+        bucket.rate = (bucket.rate + self.config.requests_per_second * self.config.recovery_step)
+            .max(self.config.min_requests_per_second)
+            .min(self.config.requests_per_second);
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+
+        bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(f64::from(self.config.burst));
+        bucket.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CoCClient;
+    use crate::testing::{MockResponse, MockServer};
+
+    #[tokio::test(start_paused = true)]
+    async fn burst_is_served_immediately() {
+        let limiter = RateLimiter::new(RateLimit::per_second(10.0).burst(3), 1);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire(0).await;
+        }
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn requests_beyond_burst_are_queued() {
+        let limiter = RateLimiter::new(RateLimit::per_second(10.0).burst(1), 1);
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire(0).await;
+        }
+
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(400), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(450), "{:?}", elapsed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keys_have_separate_buckets() {
+        let limiter = RateLimiter::new(RateLimit::per_second(1.0).burst(1), 2);
+        let start = Instant::now();
+
+        limiter.acquire(0).await;
+        limiter.acquire(1).await;
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn throttling_slows_down_and_success_recovers() {
+        let limiter = RateLimiter::new(
+            RateLimit::per_second(10.0)
+                .min_requests_per_second(2.0)
+                .recovery_step(0.1),
+            1,
+        );
+
+        limiter.throttled(0);
+        assert_eq!(limiter.current_rate(0), Some(5.0));
+
+        limiter.throttled(0);
+        limiter.throttled(0);
+        assert_eq!(limiter.current_rate(0), Some(2.0));
+
+        limiter.succeeded(0);
+        assert_eq!(limiter.current_rate(0), Some(3.0));
+
+        for _ in 0..20 {
+            limiter.succeeded(0);
+        }
+        assert_eq!(limiter.current_rate(0), Some(10.0));
+    }
+
+    #[tokio::test]
+    async fn client_backs_off_after_request_throttled() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(
+                429,
+                r#"{"reason":"requestThrottled","message":"Slow down"}"#,
+            )
+        })
+        .await;
+
+        let coc_client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .rate_limit(RateLimit::per_second(20.0))
+            .build()
+            .unwrap();
+
+        let path = format!("{}/goldpass/seasons/current", coc_client.url());
+        coc_client
+            .send_get_request_with_query(&path, Vec::new())
+            .await
+            .unwrap();
+
+        let rate = coc_client.rate_limiter().unwrap().current_rate(0);
+        assert_eq!(rate, Some(10.0));
+    }
+}
@@ -0,0 +1,251 @@
+use std::time::Duration;
+
+use reqwest::{header::HeaderMap, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio::time::Instant;
+
+use crate::{cache, client::CoCClient, errors::CoCClientError};
+
+/// Represents a deserialized response together with the metadata of the HTTP exchange.
+#[derive(Clone, Debug)]
+pub struct ApiResponse<T> {
+    /// The deserialized body.
+    pub data: T,
+    /// The status code of the response.
+    pub status_code: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// When the data expires according to `Cache-Control: max-age`, if the response allows caching.
+    pub expires_at: Option<Instant>,
+    /// The time taken to get the response, including retries and waiting for the rate limiter.
+    pub latency: Duration,
+    /// The raw body, kept when the client was built with `keep_raw_body`, or when the request was
+    /// sent with `get_api_response_with_raw_body`.
+    pub raw_body: Option<String>,
+    /// Whether the response is an expired cache entry served because the API was unavailable.
+    pub stale: bool,
+    /// The paths of the missing fields filled with empty values in lenient mode, e.g. `items[].name`.
+    pub filled_fields: Vec<String>,
+}
+
+impl<T> ApiResponse<T> {
+    /// Returns how long until the data expires, or `None` if the response does not allow caching.
+    ///
+    /// Returns `Duration::ZERO` once the data has expired.
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| expires_at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Returns the deserialized body, dropping the metadata.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl CoCClient {
+    /// Sends a GET request and returns the deserialized body together with the response metadata.
+    ///
+    /// This is how to get an `ApiResponse` from any endpoint, including the ones without a
+    /// `_response` variant such as `get_clan_information_response`. The request goes through the
+    /// same rate limiter, retries and caches as the other request methods.
+    ///
+    /// ```no_run
+    /// # async fn run(coc_client: rust_of_clans::client::CoCClient) -> Result<(), rust_of_clans::errors::CoCClientError> {
+    /// use rust_of_clans::leagues::models::League;
+    /// use rust_of_clans::client::PagedResponse;
+    ///
+    /// let path = format!("{}/leagues", coc_client.url());
+    /// let response = coc_client
+    ///     .get_api_response::<PagedResponse<League>>(&path, Vec::new())
+    ///     .await?;
+    ///
+    /// println!("{} leagues, expiring in {:?}", response.data.items.len(), response.expires_in());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The URL to which the GET request should be sent, i.e. `CoCClient::url` followed
+    ///   by the path of the endpoint, with tags percent-encoded.
+    /// * `query` - The query pairs, usually produced by `QueryParams::to_query`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `ApiResponse` on success, or a `CoCClientError`
+    /// if there was an error in the request or response.
+    pub async fn get_api_response<T>(
+        &self,
+        path: &str,
+        query: Vec<(String, String)>,
+    ) -> Result<ApiResponse<T>, CoCClientError>
+    where
+        T: DeserializeOwned,
+    {
+        self.fetch_api_response(path, query, self.keeps_raw_body())
+            .await
+    }
+
+    /// Sends a GET request like `get_api_response`, keeping the raw body in the `ApiResponse`
+    /// even if the client was not built with `keep_raw_body`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The URL to which the GET request should be sent, as for `get_api_response`.
+    /// * `query` - The query pairs, usually produced by `QueryParams::to_query`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `ApiResponse` with `raw_body` set on success,
+    /// or a `CoCClientError` if there was an error in the request or response.
+    pub async fn get_api_response_with_raw_body<T>(
+        &self,
+        path: &str,
+        query: Vec<(String, String)>,
+    ) -> Result<ApiResponse<T>, CoCClientError>
+    where
+        T: DeserializeOwned,
+    {
+        self.fetch_api_response(path, query, true).await
+    }
+
+    async fn fetch_api_response<T>(
+        &self,
+        path: &str,
+        query: Vec<(String, String)>,
+        keep_raw_body: bool,
+    ) -> Result<ApiResponse<T>, CoCClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let started = Instant::now();
+
+        let client_response = match self.send_get_request_with_query(path, query).await {
+            Ok(client_response) => client_response,
+            Err(err) => return Err(err),
+        };
+
+        let received = Instant::now();
+        let status_code = client_response.status_code;
+        let headers = client_response.headers.clone();
+        let stale = client_response.stale;
+        let raw_body = keep_raw_body.then(|| client_response.text.clone());
+
+        let (data, filled_fields) = CoCClient::deserialize_response(client_response)?;
+
+        Ok(ApiResponse {
+            data,
+            status_code,
+            expires_at: cache::expires_at(&headers, received),
+            headers,
+            latency: received.duration_since(started),
+            raw_body,
+            stale,
+            filled_fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goldpass::models::GoldPassSeason;
+    use crate::testing::{MockResponse, MockServer};
+
+    const GOLD_PASS_BODY: &str =
+        r#"{"startTime":"20230601T080000.000Z","endTime":"20230701T080000.000Z"}"#;
+
+    #[tokio::test]
+    async fn api_response_exposes_metadata() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(200, GOLD_PASS_BODY)
+                .header("Cache-Control", "public max-age=120")
+                .header("X-Custom", "custom")
+        })
+        .await;
+
+        let coc_client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .keep_raw_body(true)
+            .build()
+            .unwrap();
+
+        let response = coc_client.get_current_gold_pass_response().await.unwrap();
+
+        assert_eq!(response.status_code, StatusCode::OK);
+        assert_eq!(response.headers.get("x-custom").unwrap(), "custom");
+        assert_eq!(response.raw_body.as_deref(), Some(GOLD_PASS_BODY));
+        assert!(!response.stale);
+
+        let expires_in = response.expires_in().unwrap();
+        assert!(expires_in > Duration::from_secs(110), "{:?}", expires_in);
+        assert!(expires_in <= Duration::from_secs(120), "{:?}", expires_in);
+        assert!(response.latency < Duration::from_secs(5));
+        assert_eq!(
+            response.into_inner().start_time,
+            crate::timestamp::parse("20230601T080000.000Z").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn api_response_without_cache_control_has_no_expiry() {
+        let server =
+            MockServer::start_with_handler(|_| MockResponse::json(200, GOLD_PASS_BODY)).await;
+
+        let coc_client = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let path = format!("{}/goldpass/seasons/current", coc_client.url());
+        let response: ApiResponse<GoldPassSeason> = coc_client
+            .get_api_response(&path, Vec::new())
+            .await
+            .unwrap();
+
+        assert_eq!(response.expires_at, None);
+        assert_eq!(response.raw_body, None);
+
+        let response: ApiResponse<GoldPassSeason> = coc_client
+            .get_api_response_with_raw_body(&path, Vec::new())
+            .await
+            .unwrap();
+
+        assert_eq!(response.raw_body.as_deref(), Some(GOLD_PASS_BODY));
+    }
+
+    #[tokio::test]
+    async fn api_response_lists_filled_fields() {
+        #[derive(serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Named {
+            name: String,
+            level: i64,
+        }
+
+        let server =
+            MockServer::start_with_handler(|_| MockResponse::json(200, r#"{"name":"A"}"#)).await;
+        let path = format!("{}/named", server.url());
+
+        let standard = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+        let result = standard.get_api_response::<Named>(&path, Vec::new()).await;
+
+        assert!(matches!(result, Err(CoCClientError::Deserlisation(_))));
+
+        let lenient = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .deserialization_mode(crate::deserialization::DeserializationMode::Lenient)
+            .build()
+            .unwrap();
+        let response = lenient
+            .get_api_response::<Named>(&path, Vec::new())
+            .await
+            .unwrap();
+
+        assert_eq!(response.filled_fields, vec!["level"]);
+    }
+}
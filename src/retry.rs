@@ -0,0 +1,346 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::StatusCode;
+
+use crate::{client::ClientResponse, errors::CoCClientError};
+
+/// Represents the kinds of failures a `RetryPolicy` can retry.
+///
+/// Bad requests (400), forbidden (403) and not found (404) responses are never retried,
+/// as sending the same request again cannot succeed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RetryableErrorKind {
+    /// The request could not be sent or the response could not be read, e.g. a timeout.
+    Request,
+    /// The API answered with 500, 502 or 504.
+    ServerError,
+    /// The API answered with 503, usually because of maintenance.
+    ServiceUnavailable,
+    /// The API answered with 429 because the key was throttled.
+    Throttled,
+}
+
+impl RetryableErrorKind {
+    /// Classifies the result of a request, returning `None` if it must not be retried.
+    pub(crate) fn classify(result: &Result<ClientResponse, CoCClientError>) -> Option<Self> {
+        match result {
+            Err(CoCClientError::Request(_)) => Some(RetryableErrorKind::Request),
+            Err(_) => None,
+            Ok(client_response) => match client_response.status_code {
+                StatusCode::INTERNAL_SERVER_ERROR
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::GATEWAY_TIMEOUT => Some(RetryableErrorKind::ServerError),
+                StatusCode::SERVICE_UNAVAILABLE => Some(RetryableErrorKind::ServiceUnavailable),
+                StatusCode::TOO_MANY_REQUESTS => Some(RetryableErrorKind::Throttled),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Describes a retry about to happen, passed to the hook set with `RetryPolicy::on_retry`.
+#[derive(Clone, Debug)]
+pub struct RetryEvent {
+    /// The URL of the request being retried.
+    pub path: String,
+    /// The number of the attempt that failed, starting at 1.
+    pub attempt: u32,
+    /// The kind of failure that triggered the retry.
+    pub kind: RetryableErrorKind,
+    /// The status code of the failed attempt, if a response was received.
+    pub status_code: Option<StatusCode>,
+    /// The time waited before the next attempt.
+    pub delay: Duration,
+}
+
+type RetryHook = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+/// Configures how failed requests are retried, with exponential back-off and jitter.
+///
+/// The delay before attempt `n + 1` is `base_delay * 2^(n - 1)`, capped at `max_delay`, of which
+/// up to the `jitter` fraction is randomly removed.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The delay before the first retry.
+    pub base_delay: Duration,
+    /// The largest delay between two attempts.
+    pub max_delay: Duration,
+    /// The fraction of each delay, between 0 and 1, that is randomized.
+    pub jitter: f64,
+    /// The kinds of failures that are retried.
+    pub retry_on: Vec<RetryableErrorKind>,
+    /// The total time after which no more attempts are started.
+    pub deadline: Option<Duration>,
+    on_retry: Option<RetryHook>,
+}
+
+impl RetryPolicy {
+    /// Creates a policy making up to `max_attempts` attempts for every retryable failure.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+            retry_on: vec![
+                RetryableErrorKind::Request,
+                RetryableErrorKind::ServerError,
+                RetryableErrorKind::ServiceUnavailable,
+                RetryableErrorKind::Throttled,
+            ],
+            deadline: None,
+            on_retry: None,
+        }
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets the largest delay between two attempts.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets the fraction of each delay that is randomized, from 0 (none) to 1 (full jitter).
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the kinds of failures that are retried.
+    pub fn retry_on(mut self, retry_on: impl IntoIterator<Item = RetryableErrorKind>) -> Self {
+        self.retry_on = retry_on.into_iter().collect();
+        self
+    }
+
+    /// Sets the total time after which no more attempts are started.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets a hook called before every retry, e.g. for logging or metrics.
+    pub fn on_retry<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RetryEvent) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the delay before the attempt following `attempt`, or `None` if no retry should happen.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - The number of the attempt that failed, starting at 1.
+    /// * `kind` - The kind of failure of the attempt.
+    /// * `elapsed` - The time spent since the first attempt started.
+    pub fn next_delay(
+        &self,
+        attempt: u32,
+        kind: RetryableErrorKind,
+        elapsed: Duration,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.retry_on.contains(&kind) {
+            return None;
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        let delay = if self.jitter > 0.0 {
+            let factor = 1.0 - self.jitter * rand::thread_rng().gen::<f64>();
+            delay.mul_f64(factor)
+        } else {
+            delay
+        };
+
+        match self.deadline {
+            Some(deadline) if elapsed + delay >= deadline => None,
+            _ => Some(delay),
+        }
+    }
+
+    /// Calls the retry hook, if one is set.
+    pub(crate) fn notify(&self, event: &RetryEvent) {
+        if let Some(hook) = &self.on_retry {
+            hook(event);
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("retry_on", &self.retry_on)
+            .field("deadline", &self.deadline)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::client::CoCClient;
+    use crate::testing::{MockResponse, MockServer};
+
+    const MAINTENANCE_BODY: &str = r#"{"reason":"inMaintenance","message":"Down for maintenance"}"#;
+
+    #[test]
+    fn exponential_delays_without_jitter() {
+        let policy = RetryPolicy::new(5)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(300))
+            .jitter(0.0);
+
+        let delays: Vec<Option<Duration>> = (1..=5)
+            .map(|attempt| policy.next_delay(attempt, RetryableErrorKind::Request, Duration::ZERO))
+            .collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(100)),
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_millis(300)),
+                Some(Duration::from_millis(300)),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn jitter_shortens_delay() {
+        let policy = RetryPolicy::new(2)
+            .base_delay(Duration::from_millis(100))
+            .jitter(1.0);
+
+        for _ in 0..100 {
+            let delay = policy
+                .next_delay(1, RetryableErrorKind::Request, Duration::ZERO)
+                .unwrap();
+            assert!(delay <= Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn only_configured_kinds_are_retried() {
+        let policy = RetryPolicy::new(3).retry_on([RetryableErrorKind::ServiceUnavailable]);
+
+        assert!(policy
+            .next_delay(1, RetryableErrorKind::ServiceUnavailable, Duration::ZERO)
+            .is_some());
+        assert!(policy
+            .next_delay(1, RetryableErrorKind::Throttled, Duration::ZERO)
+            .is_none());
+    }
+
+    #[test]
+    fn deadline_stops_retries() {
+        let policy = RetryPolicy::new(10)
+            .base_delay(Duration::from_secs(1))
+            .jitter(0.0)
+            .deadline(Duration::from_secs(5));
+
+        assert!(policy
+            .next_delay(1, RetryableErrorKind::Request, Duration::from_secs(3))
+            .is_some());
+        assert!(policy
+            .next_delay(1, RetryableErrorKind::Request, Duration::from_secs(4))
+            .is_none());
+    }
+
+    fn set_up_client(server: &MockServer, policy: RetryPolicy) -> CoCClient {
+        CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .retry_policy(policy.base_delay(Duration::from_millis(1)))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn client_retries_scripted_failures() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let server =
+            MockServer::start_with_handler(move |_| match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => MockResponse::json(503, MAINTENANCE_BODY),
+                1 => MockResponse::json(500, r#"{"reason":"unknownException"}"#),
+                _ => MockResponse::json(
+                    200,
+                    r#"{"startTime":"20230601T080000.000Z","endTime":"20230701T080000.000Z"}"#,
+                ),
+            })
+            .await;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let policy = RetryPolicy::new(3).on_retry(move |event| {
+            recorded.lock().unwrap().push((event.attempt, event.kind));
+        });
+        let coc_client = set_up_client(&server, policy);
+
+        coc_client.get_current_gold_pass().await.unwrap();
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                (1, RetryableErrorKind::ServiceUnavailable),
+                (2, RetryableErrorKind::ServerError),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn client_gives_up_after_max_attempts() {
+        let server =
+            MockServer::start_with_handler(|_| MockResponse::json(503, MAINTENANCE_BODY)).await;
+        let coc_client = set_up_client(&server, RetryPolicy::new(2));
+
+        let result = coc_client.get_current_gold_pass().await;
+
+        assert!(matches!(result, Err(CoCClientError::Maintenance(_))));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn client_never_retries_not_found() {
+        let server = MockServer::start_with_handler(|_| {
+            MockResponse::json(404, r#"{"reason":"notFound","message":"Not found"}"#)
+        })
+        .await;
+        let coc_client = set_up_client(&server, RetryPolicy::new(5));
+
+        let result = coc_client.get_player_info("#2288UCQJ").await;
+
+        assert!(matches!(result, Err(CoCClientError::NotFound(_))));
+        assert_eq!(server.requests().len(), 1);
+    }
+}
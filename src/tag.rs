@@ -0,0 +1,257 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use urlencoding::encode;
+
+use crate::errors::CoCClientError;
+
+/// The characters a tag is made of, in the order of their numeric value.
+pub const TAG_ALPHABET: &str = "0289PYLQGRJCUV";
+
+/// Represents a validated player, clan or war tag, such as `#2LUGVU89Q`.
+///
+/// Tags are normalized when parsed: whitespace is removed, letters are uppercased,
+/// the `#` is added if missing, and the letter `O` is read as the digit `0`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tag(String);
+
+impl Tag {
+    /// Parses and normalizes a tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The tag, with or without the leading `#`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the normalized `Tag` on success, or a `CoCClientError::InvalidParameters`
+    /// if the tag is empty or contains characters outside of `TAG_ALPHABET`.
+    pub fn parse(input: &str) -> Result<Self, CoCClientError> {
+        let normalized: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .trim_start_matches('#')
+            .to_uppercase()
+            .replace('O', "0");
+
+        if normalized.is_empty() {
+            return Err(CoCClientError::InvalidParameters(format!(
+                "invalid tag {:?}: the tag is empty",
+                input
+            )));
+        }
+
+        if let Some(invalid) = normalized.chars().find(|c| !TAG_ALPHABET.contains(*c)) {
+            return Err(CoCClientError::InvalidParameters(format!(
+                "invalid tag {:?}: {:?} is not one of {}",
+                input, invalid, TAG_ALPHABET
+            )));
+        }
+
+        Ok(Tag(format!("#{}", normalized)))
+    }
+
+    /// Creates the tag of the account with the given numeric id.
+    ///
+    /// # Arguments
+    ///
+    /// * `high` - The high part of the id.
+    /// * `low` - The low part of the id.
+    pub fn from_id(high: u8, low: u32) -> Self {
+        let base = TAG_ALPHABET.len() as u64;
+        let mut id = (u64::from(low) << 8) + u64::from(high);
+        let mut digits = Vec::new();
+
+        loop {
+            digits.push(TAG_ALPHABET.as_bytes()[(id % base) as usize] as char);
+            id /= base;
+
+            if id == 0 {
+                break;
+            }
+        }
+
+        Tag(std::iter::once('#')
+            .chain(digits.into_iter().rev())
+            .collect())
+    }
+
+    /// Returns the numeric id of the account as its `(high, low)` parts.
+    ///
+    /// Returns `None` if the tag is too long to be an account id.
+    pub fn to_id(&self) -> Option<(u8, u32)> {
+        let base = TAG_ALPHABET.len() as u64;
+        let mut id: u64 = 0;
+
+        for c in self.0[1..].chars() {
+            let digit = TAG_ALPHABET.find(c)? as u64;
+            id = id.checked_mul(base)?.checked_add(digit)?;
+        }
+
+        let high = (id % 256) as u8;
+        let low = u32::try_from(id >> 8).ok()?;
+
+        Some((high, low))
+    }
+
+    /// Returns the tag, including the leading `#`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the tag percent-encoded for use in a request path.
+    pub fn encoded(&self) -> String {
+        encode(&self.0).into_owned()
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Tag {
+    type Err = CoCClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Tag::parse(s)
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Tag {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Tag {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Serialize for Tag {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tag {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let tag = String::deserialize(deserializer)?;
+
+        Tag::parse(&tag).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Converts a value into a validated `Tag`, so request methods accept both strings and tags.
+pub trait IntoTag {
+    /// Converts the value, returning a `CoCClientError::InvalidParameters` if it is not a valid tag.
+    fn into_tag(self) -> Result<Tag, CoCClientError>;
+}
+
+impl IntoTag for Tag {
+    fn into_tag(self) -> Result<Tag, CoCClientError> {
+        Ok(self)
+    }
+}
+
+impl IntoTag for &Tag {
+    fn into_tag(self) -> Result<Tag, CoCClientError> {
+        Ok(self.clone())
+    }
+}
+
+impl IntoTag for &str {
+    fn into_tag(self) -> Result<Tag, CoCClientError> {
+        Tag::parse(self)
+    }
+}
+
+impl IntoTag for String {
+    fn into_tag(self) -> Result<Tag, CoCClientError> {
+        Tag::parse(&self)
+    }
+}
+
+impl IntoTag for &String {
+    fn into_tag(self) -> Result<Tag, CoCClientError> {
+        Tag::parse(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_normalized() {
+        assert_eq!(Tag::parse("#2LUGVU89Q").unwrap(), "#2LUGVU89Q");
+        assert_eq!(Tag::parse("2lugvu89q").unwrap(), "#2LUGVU89Q");
+        assert_eq!(Tag::parse(" #2LUG VU89Q ").unwrap(), "#2LUGVU89Q");
+        assert_eq!(Tag::parse("#2LUGVU89O").unwrap(), "#2LUGVU890");
+        assert_eq!(Tag::parse("#0").unwrap(), "#0");
+    }
+
+    #[test]
+    fn invalid_tags_are_rejected() {
+        for input in ["", "#", "#2LUGVU89X", "#2LUG-VU89Q", "#1234"] {
+            assert!(
+                matches!(Tag::parse(input), Err(CoCClientError::InvalidParameters(_))),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn tags_round_trip_through_ids() {
+        let tag = Tag::parse("#2PP").unwrap();
+        assert_eq!(tag.to_id(), Some((0, 1)));
+        assert_eq!(Tag::from_id(0, 1), tag);
+
+        for input in ["#2LUGVU89Q", "#2288UCQJ", "#0", "#V"] {
+            let tag = Tag::parse(input).unwrap();
+            let (high, low) = tag.to_id().unwrap();
+            assert_eq!(Tag::from_id(high, low), tag, "{}", input);
+        }
+
+        assert_eq!(Tag::parse("#VVVVVVVVVVVVVVVVVVVV").unwrap().to_id(), None);
+        assert_eq!(
+            Tag::from_id(u8::MAX, u32::MAX).to_id(),
+            Some((u8::MAX, u32::MAX))
+        );
+    }
+
+    #[test]
+    fn tags_are_percent_encoded() {
+        assert_eq!(Tag::parse("#2LUGVU89Q").unwrap().encoded(), "%232LUGVU89Q");
+    }
+
+    #[test]
+    fn tags_deserialize_from_strings() {
+        let tags: Vec<Tag> = serde_json::from_str(r##"["#2lugvu89q", "#0"]"##).unwrap();
+        assert_eq!(tags, vec!["#2LUGVU89Q", "#0"]);
+        assert_eq!(
+            serde_json::to_string(&tags).unwrap(),
+            r##"["#2LUGVU89Q","#0"]"##
+        );
+
+        assert!(serde_json::from_str::<Tag>(r##""#NOT A TAG""##).is_err());
+    }
+}
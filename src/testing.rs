@@ -0,0 +1,752 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::Method;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::builder::{CoCClientBuilder, DEFAULT_VERSION};
+use crate::client::CoCClient;
+use crate::errors::CoCClientError;
+use crate::fixtures;
+
+/// The bearer token `MockServer` accepts unless configured otherwise.
+pub const MOCK_BEARER_TOKEN: &str = "MOCK_BEARER_TOKEN";
+
+/// A request received by the `MockServer`.
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The path of the request, still percent-encoded, e.g. `/v1/clans/%232PP`.
+    pub path: String,
+    /// The decoded query pairs of the request.
+    pub query: Vec<(String, String)>,
+    /// The headers of the request, with lowercase names.
+    pub headers: Vec<(String, String)>,
+    /// The body of the request.
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// Returns the decoded value of the query parameter `key`, if present.
+    pub fn query_param(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the value of the header `name`, matched case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A response served by the `MockServer`.
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    status: u16,
+    body: String,
+    headers: Vec<(String, String)>,
+    delay: Duration,
+}
+
+impl MockResponse {
+    /// Creates a response with the given status code and JSON body.
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            headers: Vec::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Creates a response with the given status code and plain text body.
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self::json(status, body).header("Content-Type", "text/plain")
+    }
+
+    /// Creates a `200 OK` response with the given JSON body, e.g. one of the `fixtures`.
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::json(200, body)
+    }
+
+    /// Creates an error response with the body the API sends for the given status code.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status code, e.g. `400`, `403`, `404`, `429`, `500` or `503`.
+    pub fn error(status: u16) -> Self {
+        let (reason, message) = match status {
+            400 => (
+                "badRequest",
+                Some("Client provided incorrect parameters for the request."),
+            ),
+            403 => ("accessDenied", Some("Invalid authorization")),
+            404 => ("notFound", None),
+            429 => (
+                "requestThrottled",
+                Some("Request was throttled, because amount of requests was above the threshold defined for the used API token."),
+            ),
+            503 => (
+                "inMaintenance",
+                Some("Service is temporarily unavailable because of maintenance."),
+            ),
+            _ => (
+                "unknownException",
+                Some("Unknown error happened when handling the request."),
+            ),
+        };
+
+        Self::api_error(status, reason, message)
+    }
+
+    /// Creates an error response with the given reason and message.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status code of the response.
+    /// * `reason` - The reason of the error, e.g. `privateWarLog`.
+    /// * `message` - The message of the error, if any.
+    pub fn api_error(status: u16, reason: &str, message: Option<&str>) -> Self {
+        let body = match message {
+            Some(message) => serde_json::json!({"reason": reason, "message": message}),
+            None => serde_json::json!({ "reason": reason }),
+        };
+
+        Self::json(status, body.to_string())
+    }
+
+    /// Creates the `403` response the API sends for a key not bound to the caller's IP address.
+    pub fn invalid_ip(ip: &str) -> Self {
+        Self::api_error(
+            403,
+            "accessDenied.invalidIp",
+            Some(&format!(
+                "Invalid authorization: API key does not allow access from IP {}",
+                ip
+            )),
+        )
+    }
+
+    /// Creates the `403` response the API sends for the war endpoints of a clan with a private war log.
+    pub fn private_war_log() -> Self {
+        Self::api_error(
+            403,
+            "privateWarLog",
+            Some("Access denied, clan war log is private."),
+        )
+    }
+
+    /// Adds a header to the response.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Delays this response, on top of the latency of the server.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Route {
+    method: Method,
+    segments: Vec<String>,
+    response: MockResponse,
+}
+
+impl Route {
+    /// Returns whether the route serves `path`, where `{...}` segments match any value.
+    fn matches(&self, method: &str, path: &str) -> bool {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        self.method.as_str() == method
+            && self.segments.len() == segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(segments)
+                .all(|(pattern, segment)| pattern.starts_with('{') || *pattern == segment)
+    }
+}
+
+type Handler = dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync;
+
+/// Builder for `MockServer`, starting from a route for every endpoint of the API.
+#[derive(Clone)]
+pub struct MockServerBuilder {
+    bearer_token: Option<String>,
+    latency: Duration,
+    routes: Vec<Route>,
+    handler: Option<Arc<Handler>>,
+}
+
+impl fmt::Debug for MockServerBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockServerBuilder")
+            .field("bearer_token", &self.bearer_token)
+            .field("latency", &self.latency)
+            .field("routes", &self.routes)
+            .field("handler", &self.handler.is_some())
+            .finish()
+    }
+}
+
+impl Default for MockServerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockServerBuilder {
+    /// Creates a builder that serves the `fixtures` for every endpoint, and accepts `MOCK_BEARER_TOKEN`.
+    pub fn new() -> Self {
+        let builder = MockServerBuilder {
+            bearer_token: Some(String::from(MOCK_BEARER_TOKEN)),
+            latency: Duration::ZERO,
+            routes: Vec::new(),
+            handler: None,
+        };
+
+        let league = first_item(fixtures::LEAGUES);
+        let war_league = first_item(fixtures::WAR_LEAGUES);
+        let builder_base_league = first_item(fixtures::BUILDER_BASE_LEAGUES);
+        let capital_league = first_item(fixtures::CAPITAL_LEAGUES);
+        let location = first_item(fixtures::LOCATIONS);
+
+        builder
+            .get("/clans", fixtures::CLAN_SEARCH)
+            .get("/clans/{clanTag}", fixtures::CLAN)
+            .get("/clans/{clanTag}/members", fixtures::CLAN_MEMBERS)
+            .get("/clans/{clanTag}/warlog", fixtures::CLAN_WAR_LOG)
+            .get("/clans/{clanTag}/currentwar", fixtures::CLAN_WAR_IN_WAR)
+            .get(
+                "/clans/{clanTag}/currentwar/leaguegroup",
+                fixtures::CLAN_WAR_LEAGUE_GROUP,
+            )
+            .get(
+                "/clans/{clanTag}/capitalraidseasons",
+                fixtures::CLAN_CAPITAL_RAID_SEASONS,
+            )
+            .get(
+                "/clanwarleagues/wars/{warTag}",
+                fixtures::CLAN_WAR_LEAGUE_WAR,
+            )
+            .get("/players/{playerTag}", fixtures::PLAYER)
+            .route(
+                Method::POST,
+                "/players/{playerTag}/verifytoken",
+                MockResponse::ok(fixtures::VERIFY_TOKEN),
+            )
+            .get("/leagues", fixtures::LEAGUES)
+            .get("/leagues/{leagueId}", league)
+            .get("/leagues/{leagueId}/seasons", fixtures::LEAGUE_SEASONS)
+            .get(
+                "/leagues/{leagueId}/seasons/{seasonId}",
+                fixtures::LEAGUE_SEASON_RANKINGS,
+            )
+            .get("/warleagues", fixtures::WAR_LEAGUES)
+            .get("/warleagues/{leagueId}", war_league)
+            .get("/builderbaseleagues", fixtures::BUILDER_BASE_LEAGUES)
+            .get("/builderbaseleagues/{leagueId}", builder_base_league)
+            .get("/capitalleagues", fixtures::CAPITAL_LEAGUES)
+            .get("/capitalleagues/{leagueId}", capital_league)
+            .get("/locations", fixtures::LOCATIONS)
+            .get("/locations/{locationId}", location)
+            .get(
+                "/locations/{locationId}/rankings/clans",
+                fixtures::LOCATION_CLAN_RANKINGS,
+            )
+            .get(
+                "/locations/{locationId}/rankings/players",
+                fixtures::LOCATION_PLAYER_RANKINGS,
+            )
+            .get(
+                "/locations/{locationId}/rankings/players-builder-base",
+                fixtures::LOCATION_PLAYER_BUILDER_BASE_RANKINGS,
+            )
+            .get(
+                "/locations/{locationId}/rankings/clans-builder-base",
+                fixtures::LOCATION_CLAN_BUILDER_BASE_RANKINGS,
+            )
+            .get(
+                "/locations/{locationId}/rankings/capitals",
+                fixtures::LOCATION_CAPITAL_RANKINGS,
+            )
+            .get("/labels/clans", fixtures::LABELS_CLANS)
+            .get("/labels/players", fixtures::LABELS_PLAYERS)
+            .get("/goldpass/seasons/current", fixtures::GOLD_PASS)
+    }
+
+    /// Sets the bearer token requests must carry, or `None` to accept any request.
+    ///
+    /// Requests with another token are answered with `403 accessDenied`.
+    pub fn bearer_token(mut self, bearer_token: Option<&str>) -> Self {
+        self.bearer_token = bearer_token.map(String::from);
+        self
+    }
+
+    /// Delays every response by `latency`.
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Serves `response` for requests matching `method` and `path`, replacing any earlier route.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method of the request.
+    /// * `path` - The path of the endpoint without the API version, e.g. `/clans/{clanTag}/warlog`.
+    ///   Segments in braces match any value.
+    /// * `response` - The response to serve.
+    pub fn route(mut self, method: Method, path: &str, response: MockResponse) -> Self {
+        let segments: Vec<String> = path
+            .trim_matches('/')
+            .split('/')
+            .map(String::from)
+            .collect();
+
+        self.routes
+            .retain(|route| route.method != method || route.segments != segments);
+        self.routes.push(Route {
+            method,
+            segments,
+            response,
+        });
+        self
+    }
+
+    /// Answers every request with `handler` instead of the routes, e.g. to inspect requests or
+    /// to change responses over time.
+    ///
+    /// The bearer token and latency still apply.
+    pub fn handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    fn get(self, path: &str, body: impl Into<String>) -> Self {
+        self.route(Method::GET, path, MockResponse::ok(body))
+    }
+
+    /// Starts the server on a random local port.
+    pub async fn start(self) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("cannot bind the mock server to a local port");
+        let url = format!(
+            "http://{}",
+            listener
+                .local_addr()
+                .expect("cannot get the address of the mock server")
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let bearer_token = self.bearer_token.clone();
+        let builder = Arc::new(self);
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let builder = builder.clone();
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let _ = serve_connection(stream, builder, recorded).await;
+                });
+            }
+        });
+
+        MockServer {
+            url,
+            requests,
+            bearer_token,
+        }
+    }
+
+    fn respond(&self, request: &RecordedRequest) -> MockResponse {
+        if let Some(bearer_token) = &self.bearer_token {
+            let expected = format!("Bearer {}", bearer_token);
+            if request.header("authorization") != Some(expected.as_str()) {
+                return MockResponse::error(403);
+            }
+        }
+
+        if let Some(handler) = &self.handler {
+            return handler(request);
+        }
+
+        let prefix = format!("/{}", DEFAULT_VERSION);
+        let path = request.path.strip_prefix(&prefix).unwrap_or(&request.path);
+        let path = urlencoding::decode(path)
+            .map(|path| path.into_owned())
+            .unwrap_or_else(|_| path.to_string());
+
+        self.routes
+            .iter()
+            .rev()
+            .find(|route| route.matches(&request.method, &path))
+            .map(|route| route.response.clone())
+            .unwrap_or_else(|| MockResponse::error(404))
+    }
+}
+
+/// A local stand-in for the Clash of Clans API, serving the `fixtures` or configured responses.
+///
+/// The server runs until the tokio runtime it was started on shuts down.
+pub struct MockServer {
+    url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    bearer_token: Option<String>,
+}
+
+impl MockServer {
+    /// Creates a `MockServerBuilder` to configure routes, the bearer token and latency.
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::new()
+    }
+
+    /// Starts a server serving the `fixtures` for every endpoint.
+    pub async fn start() -> Self {
+        MockServerBuilder::new().start().await
+    }
+
+    /// Starts a server answering every request with `handler`, whatever its bearer token.
+    pub async fn start_with_handler<F>(handler: F) -> Self
+    where
+        F: Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        MockServerBuilder::new()
+            .bearer_token(None)
+            .handler(handler)
+            .start()
+            .await
+    }
+
+    /// Returns the base URL of the server, to pass to `CoCClientBuilder::base_url`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns all requests received so far, in arrival order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns a `CoCClientBuilder` pointed at the server, with the bearer token it accepts.
+    pub fn client_builder(&self) -> CoCClientBuilder {
+        let bearer_token = self.bearer_token.as_deref().unwrap_or(MOCK_BEARER_TOKEN);
+
+        CoCClient::builder(bearer_token).base_url(self.url())
+    }
+
+    /// Returns a `CoCClient` pointed at the server.
+    pub fn client(&self) -> Result<CoCClient, CoCClientError> {
+        self.client_builder().build()
+    }
+}
+
+/// Reads one HTTP/1.1 request from `stream` and answers it.
+async fn serve_connection(
+    mut stream: TcpStream,
+    builder: Arc<MockServerBuilder>,
+    recorded: Arc<Mutex<Vec<RecordedRequest>>>,
+) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while buffer.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let body = String::from_utf8_lossy(&buffer[header_end..]).into_owned();
+
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target, Vec::new()),
+    };
+
+    let request = RecordedRequest {
+        method,
+        path,
+        query,
+        headers,
+        body,
+    };
+    recorded.lock().unwrap().push(request.clone());
+
+    let response = builder.respond(&request);
+
+    let delay = builder.latency + response.delay;
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+
+    let mut headers = response.headers;
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        headers.push((
+            String::from("Content-Type"),
+            String::from("application/json"),
+        ));
+    }
+
+    let mut raw = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (name, value) in headers {
+        raw.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    raw.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.body.len(),
+        response.body
+    ));
+    stream.write_all(raw.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn decode(value: &str) -> String {
+    let value = value.replace('+', " ");
+    urlencoding::decode(&value)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or(value)
+}
+
+/// Returns the first item of a paged fixture, for the endpoints returning a single item.
+fn first_item(page: &str) -> String {
+    let page: serde_json::Value = serde_json::from_str(page).expect("fixtures are valid JSON");
+
+    page["items"][0].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clans::models::ClanWarState;
+    use crate::clans::search::ClanSearch;
+    use crate::params::PageParams;
+
+    #[tokio::test]
+    async fn mock_server_serves_fixtures() {
+        let server = MockServer::start().await;
+        let client = server.client().unwrap();
+
+        let clan = client.get_clan_information("#2PP").await.unwrap();
+        let war = client.get_current_clan_war("#2PP").await.unwrap();
+        let league = client.get_war_league_info(48000000).await.unwrap();
+
+        assert_eq!(clan.name, "Golden Clan");
+        assert_eq!(war.state, ClanWarState::InWar);
+        assert_eq!(league.name, "Unranked");
+        assert_eq!(server.requests()[0].path, "/v1/clans/%232PP");
+    }
+
+    #[tokio::test]
+    async fn mock_server_serves_configured_routes() {
+        let server = MockServer::builder()
+            .route(
+                Method::GET,
+                "/clans/{clanTag}/currentwar",
+                MockResponse::ok(fixtures::CLAN_WAR_PREPARATION),
+            )
+            .route(
+                Method::GET,
+                "/clans/#8QU8J9LP/warlog",
+                MockResponse::private_war_log(),
+            )
+            .start()
+            .await;
+        let client = server.client().unwrap();
+
+        let war = client.get_current_clan_war("#2PP").await.unwrap();
+        let err = client
+            .get_clan_war_log("#8QU8J9LP", None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(war.state, ClanWarState::Preparation);
+        assert!(matches!(err, CoCClientError::PrivateWarLog(_)), "{}", err);
+        assert!(client.get_clan_war_log("#2PP", None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn mock_server_serves_api_errors() {
+        let server = MockServer::builder()
+            .route(
+                Method::GET,
+                "/players/{playerTag}",
+                MockResponse::error(404),
+            )
+            .route(Method::GET, "/clans", MockResponse::error(400))
+            .route(Method::GET, "/locations", MockResponse::error(429))
+            .route(
+                Method::GET,
+                "/goldpass/seasons/current",
+                MockResponse::error(503),
+            )
+            .start()
+            .await;
+        let client = server.client().unwrap();
+
+        let not_found = client.get_player_info("#2288UCQJ").await.unwrap_err();
+        let bad_request = client
+            .get_clans(&ClanSearch::new().name("Golden"))
+            .await
+            .unwrap_err();
+        let throttled = client.get_locations(None).await.unwrap_err();
+        let maintenance = client.get_current_gold_pass().await.unwrap_err();
+
+        assert!(
+            matches!(not_found, CoCClientError::NotFound(_)),
+            "{}",
+            not_found
+        );
+        assert!(
+            matches!(bad_request, CoCClientError::ClientError(_)),
+            "{}",
+            bad_request
+        );
+        assert!(
+            matches!(throttled, CoCClientError::Throttled(_)),
+            "{}",
+            throttled
+        );
+        assert!(
+            matches!(maintenance, CoCClientError::Maintenance(_)),
+            "{}",
+            maintenance
+        );
+    }
+
+    #[tokio::test]
+    async fn mock_server_checks_the_bearer_token() {
+        let server = MockServer::builder()
+            .bearer_token(Some("secret"))
+            .start()
+            .await;
+
+        let wrong = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+        let err = wrong.get_current_gold_pass().await.unwrap_err();
+
+        assert!(matches!(err, CoCClientError::AccessDenied(_)), "{}", err);
+        assert!(server
+            .client()
+            .unwrap()
+            .get_current_gold_pass()
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn mock_server_answers_with_handler() {
+        let server = MockServer::builder()
+            .handler(|request| match request.query_param("limit") {
+                Some(limit) => MockResponse::ok(format!(r#"{{"items":[],"limit":{}}}"#, limit)),
+                None => MockResponse::text(502, "Bad gateway"),
+            })
+            .start()
+            .await;
+        let client = server.client().unwrap();
+
+        let labels = client
+            .get_clan_labels(Some(PageParams::new().limit(2)))
+            .await
+            .unwrap();
+        let err = client.get_player_labels(None).await.unwrap_err();
+        let denied = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .build()
+            .unwrap()
+            .get_clan_labels(None)
+            .await
+            .unwrap_err();
+
+        assert!(labels.items.is_empty());
+        assert_eq!(err.status_code(), Some(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(
+            matches!(denied, CoCClientError::AccessDenied(_)),
+            "{}",
+            denied
+        );
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn mock_server_injects_latency() {
+        let server = MockServer::builder()
+            .latency(Duration::from_secs(2))
+            .route(
+                Method::GET,
+                "/labels/clans",
+                MockResponse::ok(fixtures::LABELS_CLANS).delay(Duration::from_secs(3)),
+            )
+            .start()
+            .await;
+        let client = server.client().unwrap();
+
+        let started = tokio::time::Instant::now();
+        client.get_current_gold_pass().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(2));
+
+        let started = tokio::time::Instant::now();
+        client.get_clan_labels(None).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(5));
+    }
+}
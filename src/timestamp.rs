@@ -0,0 +1,170 @@
+use serde::{Deserialize, Deserializer, Serializer};
+
+#[cfg(not(any(feature = "time", feature = "chrono")))]
+compile_error!("enable either the `time` or the `chrono` feature to deserialize timestamps");
+
+/// Represents a point in time returned by the API, in UTC.
+///
+/// This is `chrono::DateTime<Utc>` with the `chrono` feature, and `time::OffsetDateTime` otherwise.
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::Utc>;
+
+/// Represents a point in time returned by the API, in UTC.
+///
+/// This is `chrono::DateTime<Utc>` with the `chrono` feature, and `time::OffsetDateTime` otherwise.
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+pub type Timestamp = time::OffsetDateTime;
+
+#[cfg(feature = "chrono")]
+const API_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Parses a timestamp in the API's format, e.g. `20230601T080000.000Z`.
+///
+/// Returns `None` if the input is not in the API's format.
+#[cfg(feature = "chrono")]
+pub fn parse(input: &str) -> Option<Timestamp> {
+    chrono::NaiveDateTime::parse_from_str(input, API_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Formats a timestamp in the API's format, e.g. `20230601T080000.000Z`.
+#[cfg(feature = "chrono")]
+pub fn format(timestamp: &Timestamp) -> String {
+    timestamp.format(API_FORMAT).to_string()
+}
+
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+const API_FORMAT: &[time::format_description::FormatItem<'static>] = time::macros::format_description!(
+    "[year][month][day]T[hour][minute][second].[subsecond digits:3]Z"
+);
+
+/// Parses a timestamp in the API's format, e.g. `20230601T080000.000Z`.
+///
+/// Returns `None` if the input is not in the API's format.
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+pub fn parse(input: &str) -> Option<Timestamp> {
+    time::PrimitiveDateTime::parse(input, API_FORMAT)
+        .ok()
+        .map(|primitive| primitive.assume_utc())
+}
+
+/// Formats a timestamp in the API's format, e.g. `20230601T080000.000Z`.
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+pub fn format(timestamp: &Timestamp) -> String {
+    timestamp
+        .to_offset(time::UtcOffset::UTC)
+        .format(API_FORMAT)
+        .unwrap_or_default()
+}
+
+/// (De)serializes a `Timestamp` in the API's format, for use with `#[serde(with = "...")]`.
+pub mod api_format {
+    use super::*;
+
+    pub fn serialize<S>(timestamp: &Timestamp, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format(timestamp))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Timestamp, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let input = String::deserialize(deserializer)?;
+
+        parse(&input)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp {:?}", input)))
+    }
+}
+
+/// (De)serializes an `Option<Timestamp>` in the API's format, for use with `#[serde(with = "...")]`.
+pub mod option_api_format {
+    use super::*;
+
+    pub fn serialize<S>(timestamp: &Option<Timestamp>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match timestamp {
+            Some(timestamp) => serializer.serialize_some(&format(timestamp)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Timestamp>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Some(input) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+
+        parse(&input)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp {:?}", input)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    struct Season {
+        #[serde(with = "api_format")]
+        start_time: Timestamp,
+        #[serde(default, with = "option_api_format")]
+        end_time: Option<Timestamp>,
+    }
+
+    #[test]
+    fn timestamps_round_trip_in_api_format() {
+        let json = r#"{"startTime":"20230601T080000.000Z","endTime":"20230701T080000.500Z"}"#;
+
+        let season: Season = serde_json::from_str(json).unwrap();
+
+        let duration = season.end_time.unwrap() - season.start_time;
+        assert_eq!(duration.num_seconds_compat(), 30 * 24 * 60 * 60);
+        assert_eq!(serde_json::to_string(&season).unwrap(), json);
+    }
+
+    #[test]
+    fn missing_optional_timestamps_are_none() {
+        let season: Season =
+            serde_json::from_str(r#"{"startTime":"20230601T080000.000Z"}"#).unwrap();
+
+        assert!(season.end_time.is_none());
+        assert!(parse("20230601T080000.000Z").unwrap() < parse("20230601T080001.000Z").unwrap());
+    }
+
+    #[test]
+    fn invalid_timestamps_are_rejected() {
+        assert!(parse("2023-06-01T08:00:00Z").is_none());
+        assert!(serde_json::from_str::<Season>(r#"{"startTime":"yesterday"}"#).is_err());
+    }
+
+    /// Reads whole seconds from the duration type of either backend.
+    trait NumSecondsCompat {
+        fn num_seconds_compat(&self) -> i64;
+    }
+
+    #[cfg(feature = "chrono")]
+    impl NumSecondsCompat for chrono::Duration {
+        fn num_seconds_compat(&self) -> i64 {
+            self.num_seconds()
+        }
+    }
+
+    #[cfg(all(feature = "time", not(feature = "chrono")))]
+    impl NumSecondsCompat for time::Duration {
+        fn num_seconds_compat(&self) -> i64 {
+            self.whole_seconds()
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+
+use crate::errors::{ApiError, CoCClientError};
+
+/// The reason returned by the API when a key is used from an IP address it is not bound to.
+const INVALID_IP_REASON: &str = "accessDenied.invalidIp";
+
+/// How long a key is benched after being throttled or denied, unless configured otherwise.
+const DEFAULT_BENCH_DURATION: Duration = Duration::from_secs(60);
+
+/// Represents an API key from the developer portal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiKey {
+    /// The bearer token of the key.
+    pub token: String,
+    /// The IP addresses the key is bound to. Empty if unknown.
+    pub allowed_ips: Vec<IpAddr>,
+}
+
+impl ApiKey {
+    /// Creates a key without any known IP binding.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            allowed_ips: Vec::new(),
+        }
+    }
+
+    /// Sets the IP addresses the key is bound to.
+    pub fn allowed_ips(mut self, allowed_ips: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.allowed_ips = allowed_ips.into_iter().collect();
+        self
+    }
+
+    /// Returns whether the key can be used from `egress_ip`.
+    ///
+    /// Keys without known IP bindings, and any key when the egress IP is unknown, are assumed to match.
+    pub fn matches_ip(&self, egress_ip: Option<IpAddr>) -> bool {
+        match egress_ip {
+            Some(egress_ip) => self.allowed_ips.is_empty() || self.allowed_ips.contains(&egress_ip),
+            None => true,
+        }
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(token: String) -> Self {
+        ApiKey::new(token)
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(token: &str) -> Self {
+        ApiKey::new(token)
+    }
+}
+
+/// Represents the strategy used to pick the key for the next request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeySelection {
+    /// Uses the available keys in turn.
+    #[default]
+    RoundRobin,
+    /// Uses the available key that was throttled the longest time ago, or never.
+    LeastRecentlyThrottled,
+}
+
+/// Represents the usage counters and health of a key in a `TokenPool`.
+#[derive(Clone, Debug, Default)]
+pub struct KeyUsage {
+    /// The first and last characters of the token, to tell keys apart without exposing them.
+    pub token_hint: String,
+    /// The number of requests sent with the key.
+    pub requests: u64,
+    /// The number of responses with status 429 received for the key.
+    pub throttled: u64,
+    /// The number of `accessDenied.invalidIp` responses received for the key.
+    pub denied: u64,
+    /// When the key was last throttled.
+    pub last_throttled: Option<Instant>,
+    /// The key is not used until this instant, unless no other key is available.
+    pub benched_until: Option<Instant>,
+}
+
+impl KeyUsage {
+    /// Returns whether the key is benched at `now`.
+    pub fn is_benched(&self, now: Instant) -> bool {
+        self.benched_until.is_some_and(|until| until > now)
+    }
+}
+
+#[derive(Debug)]
+struct PoolState {
+    next: usize,
+    usage: Vec<KeyUsage>,
+    egress_ip: Option<IpAddr>,
+}
+
+/// Represents a pool of API keys that requests are distributed across.
+///
+/// Keys that get throttled (429) or rejected for their IP (403 `accessDenied.invalidIp`) are
+/// benched for a while, and keys bound to other IPs than the current egress IP are skipped.
+#[derive(Debug)]
+pub struct TokenPool {
+    keys: Vec<ApiKey>,
+    selection: KeySelection,
+    bench_duration: Duration,
+    state: Mutex<PoolState>,
+}
+
+impl TokenPool {
+    /// Creates a pool from the given keys, using round-robin selection.
+    pub fn new<K>(keys: impl IntoIterator<Item = K>) -> Self
+    where
+        K: Into<ApiKey>,
+    {
+        let keys: Vec<ApiKey> = keys.into_iter().map(Into::into).collect();
+
+        let usage = keys
+            .iter()
+            .map(|key| KeyUsage {
+                token_hint: token_hint(&key.token),
+                ..KeyUsage::default()
+            })
+            .collect();
+
+        Self {
+            keys,
+            selection: KeySelection::default(),
+            bench_duration: DEFAULT_BENCH_DURATION,
+            state: Mutex::new(PoolState {
+                next: 0,
+                usage,
+                egress_ip: None,
+            }),
+        }
+    }
+
+    /// Sets the strategy used to pick the key for each request.
+    pub fn selection(mut self, selection: KeySelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets how long a throttled or denied key is benched.
+    pub fn bench_duration(mut self, bench_duration: Duration) -> Self {
+        self.bench_duration = bench_duration;
+        self
+    }
+
+    /// Returns the keys of the pool.
+    pub fn keys(&self) -> &[ApiKey] {
+        &self.keys
+    }
+
+    /// Sets the public IP address requests are currently sent from, or `None` if unknown.
+    pub fn set_egress_ip(&self, egress_ip: Option<IpAddr>) {
+        self.state.lock().unwrap().egress_ip = egress_ip;
+    }
+
+    /// Returns the public IP address requests are currently sent from, if known.
+    pub fn egress_ip(&self) -> Option<IpAddr> {
+        self.state.lock().unwrap().egress_ip
+    }
+
+    /// Returns a snapshot of the usage counters of every key, in the order of `keys`.
+    pub fn usage(&self) -> Vec<KeyUsage> {
+        self.state.lock().unwrap().usage.clone()
+    }
+
+    /// Returns the token of the key at `index`.
+    pub(crate) fn token(&self, index: usize) -> &str {
+        &self.keys[index].token
+    }
+
+    /// Picks the key for the next request and counts the request against it.
+    ///
+    /// Benched keys are only used when every key matching the egress IP is benched, in which
+    /// case the one whose bench ends first is picked.
+    pub(crate) fn acquire(&self) -> Result<usize, CoCClientError> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let count = self.keys.len();
+
+        let candidates: Vec<usize> = (0..count)
+            .map(|offset| (state.next + offset) % count)
+            .filter(|&index| self.keys[index].matches_ip(state.egress_ip))
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(CoCClientError::InvalidConfiguration(
+                match state.egress_ip {
+                    Some(egress_ip) => {
+                        format!("no API key is bound to the egress IP {}", egress_ip)
+                    }
+                    None => String::from("the token pool has no API keys"),
+                },
+            ));
+        }
+
+        let available: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&index| !state.usage[index].is_benched(now))
+            .collect();
+
+        let index = if available.is_empty() {
+            candidates
+                .iter()
+                .copied()
+                .min_by_key(|&index| state.usage[index].benched_until)
+                .unwrap()
+        } else {
+            match self.selection {
+                KeySelection::RoundRobin => available[0],
+                KeySelection::LeastRecentlyThrottled => available
+                    .iter()
+                    .copied()
+                    .min_by_key(|&index| state.usage[index].last_throttled)
+                    .unwrap(),
+            }
+        };
+
+        state.next = (index + 1) % count;
+        state.usage[index].requests += 1;
+
+        Ok(index)
+    }
+
+    /// Records the outcome of a request sent with the key at `index`, benching it if needed.
+    pub(crate) fn report(&self, index: usize, status_code: StatusCode, body: &str) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let usage = &mut state.usage[index];
+
+        if status_code == StatusCode::TOO_MANY_REQUESTS {
+            usage.throttled += 1;
+            usage.last_throttled = Some(now);
+            usage.benched_until = Some(now + self.bench_duration);
+        } else if status_code == StatusCode::FORBIDDEN && is_invalid_ip(body) {
+            usage.denied += 1;
+            usage.benched_until = Some(now + self.bench_duration);
+        }
+    }
+}
+
+fn is_invalid_ip(body: &str) -> bool {
+    ApiError::parse(StatusCode::FORBIDDEN, body)
+        .is_some_and(|error| error.reason == INVALID_IP_REASON)
+}
+
+fn token_hint(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+
+    let start: String = chars[..4].iter().collect();
+    let end: String = chars[chars.len() - 4..].iter().collect();
+
+    format!("{}…{}", start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::CoCClientBuilder;
+    use crate::testing::{MockResponse, MockServer};
+
+    const THROTTLED_BODY: &str = r#"{"reason":"requestThrottled","message":"Slow down"}"#;
+
+    const INVALID_IP_BODY: &str =
+        r#"{"reason":"accessDenied.invalidIp","message":"Invalid authorization"}"#;
+
+    #[test]
+    fn round_robin_selection() {
+        let pool = TokenPool::new(["a", "b", "c"]);
+
+        let picked: Vec<usize> = (0..4).map(|_| pool.acquire().unwrap()).collect();
+
+        assert_eq!(picked, vec![0, 1, 2, 0]);
+        assert_eq!(pool.usage()[0].requests, 2);
+    }
+
+    #[test]
+    fn throttled_key_is_benched() {
+        let pool = TokenPool::new(["a", "b"]);
+
+        pool.report(0, StatusCode::TOO_MANY_REQUESTS, THROTTLED_BODY);
+
+        let picked: Vec<usize> = (0..3).map(|_| pool.acquire().unwrap()).collect();
+
+        assert_eq!(picked, vec![1, 1, 1]);
+        assert_eq!(pool.usage()[0].throttled, 1);
+    }
+
+    #[test]
+    fn invalid_ip_key_is_benched() {
+        let pool = TokenPool::new(["a", "b"]);
+
+        pool.report(1, StatusCode::FORBIDDEN, INVALID_IP_BODY);
+        pool.report(0, StatusCode::FORBIDDEN, r#"{"reason":"accessDenied"}"#);
+
+        assert_eq!(pool.acquire().unwrap(), 0);
+        assert_eq!(pool.acquire().unwrap(), 0);
+        assert_eq!(pool.usage()[1].denied, 1);
+        assert_eq!(pool.usage()[0].denied, 0);
+    }
+
+    #[test]
+    fn all_keys_benched_uses_first_to_recover() {
+        let pool = TokenPool::new(["a", "b"]);
+
+        pool.report(1, StatusCode::TOO_MANY_REQUESTS, THROTTLED_BODY);
+        pool.report(0, StatusCode::TOO_MANY_REQUESTS, THROTTLED_BODY);
+
+        assert_eq!(pool.acquire().unwrap(), 1);
+    }
+
+    #[test]
+    fn keys_bound_to_other_ips_are_skipped() {
+        let home: IpAddr = "203.0.113.1".parse().unwrap();
+        let office: IpAddr = "203.0.113.2".parse().unwrap();
+
+        let pool = TokenPool::new([
+            ApiKey::new("a").allowed_ips([home]),
+            ApiKey::new("b").allowed_ips([office]),
+            ApiKey::new("c"),
+        ]);
+
+        pool.set_egress_ip(Some(office));
+
+        let picked: Vec<usize> = (0..4).map(|_| pool.acquire().unwrap()).collect();
+
+        assert_eq!(picked, vec![1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn no_key_for_egress_ip() {
+        let pool = TokenPool::new([ApiKey::new("a").allowed_ips(["203.0.113.1".parse().unwrap()])]);
+
+        pool.set_egress_ip(Some("203.0.113.2".parse().unwrap()));
+
+        assert!(matches!(
+            pool.acquire(),
+            Err(CoCClientError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn least_recently_throttled_selection() {
+        let pool = TokenPool::new(["a", "b", "c"])
+            .selection(KeySelection::LeastRecentlyThrottled)
+            .bench_duration(Duration::ZERO);
+
+        pool.report(0, StatusCode::TOO_MANY_REQUESTS, THROTTLED_BODY);
+        pool.report(2, StatusCode::TOO_MANY_REQUESTS, THROTTLED_BODY);
+        pool.report(1, StatusCode::TOO_MANY_REQUESTS, THROTTLED_BODY);
+
+        assert_eq!(pool.acquire().unwrap(), 0);
+    }
+
+    #[test]
+    fn token_hint_hides_token() {
+        assert_eq!(token_hint("abcdefghijkl"), "abcd…ijkl");
+        assert_eq!(token_hint("short"), "*****");
+    }
+
+    #[tokio::test]
+    async fn client_rotates_keys_and_benches_throttled_key() {
+        let server =
+            MockServer::start_with_handler(|request| match request.header("authorization") {
+                Some("Bearer first-key-token") => MockResponse::json(429, THROTTLED_BODY),
+                _ => MockResponse::json(200, "{}"),
+            })
+            .await;
+
+        let coc_client = CoCClientBuilder::with_token_pool(TokenPool::new([
+            "first-key-token",
+            "second-key-token",
+        ]))
+        .base_url(server.url())
+        .build()
+        .unwrap();
+
+        let path = format!("{}/goldpass/seasons/current", coc_client.url());
+        for _ in 0..3 {
+            coc_client
+                .send_get_request_with_query(&path, Vec::new())
+                .await
+                .unwrap();
+        }
+
+        let tokens: Vec<String> = server
+            .requests()
+            .iter()
+            .map(|request| request.header("authorization").unwrap().to_string())
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                "Bearer first-key-token",
+                "Bearer second-key-token",
+                "Bearer second-key-token",
+            ]
+        );
+
+        let usage = coc_client.token_pool().usage();
+        assert_eq!(usage[0].requests, 1);
+        assert_eq!(usage[0].throttled, 1);
+        assert_eq!(usage[1].requests, 2);
+    }
+}
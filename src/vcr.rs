@@ -0,0 +1,399 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::client::ClientResponse;
+use crate::errors::CoCClientError;
+
+/// The text bearer tokens are replaced with in recorded cassettes.
+pub const REDACTED: &str = "REDACTED";
+
+/// Whether a `Cassette` records requests sent to the API, or replays previously recorded ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Requests are sent to the API, and every request and response is written to the cassette.
+    Record,
+    /// Requests are answered from the cassette, without contacting the API.
+    Replay,
+}
+
+/// Represents a request stored in a cassette.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CassetteRequest {
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The path of the request, relative to the versioned base URL, e.g. `/clans/%232PP`.
+    pub path: String,
+    /// The query pairs of the request.
+    pub query: Vec<(String, String)>,
+    /// The JSON body of the request, if any.
+    pub body: Option<String>,
+}
+
+impl CassetteRequest {
+    pub(crate) fn new(
+        method: &Method,
+        path: &str,
+        query: &[(String, String)],
+        body: Option<&str>,
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_vec(),
+            body: body.map(String::from),
+        }
+    }
+
+    /// Returns whether both requests are the same, regardless of the order of their query pairs.
+    fn matches(&self, other: &CassetteRequest) -> bool {
+        let mut query = self.query.clone();
+        let mut other_query = other.query.clone();
+        query.sort();
+        other_query.sort();
+
+        self.method == other.method
+            && self.path == other.path
+            && query == other_query
+            && self.body == other.body
+    }
+}
+
+/// Represents a response stored in a cassette.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CassetteResponse {
+    /// The status code of the response.
+    pub status: u16,
+    /// The headers of the response.
+    pub headers: Vec<(String, String)>,
+    /// The raw body of the response.
+    pub body: String,
+}
+
+/// Represents a request and the response the API answered it with.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Interaction {
+    /// The request sent to the API.
+    pub request: CassetteRequest,
+    /// The response the API answered with.
+    pub response: CassetteResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// Whether each interaction has been replayed already.
+    replayed: Vec<bool>,
+}
+
+/// Represents a file of recorded requests and responses, for deterministic tests without network access.
+///
+/// In record mode, every request the client sends to the API is appended to the cassette, and the
+/// file is rewritten after each response. Bearer tokens are never stored, and are replaced with
+/// `REDACTED` wherever they appear in a response.
+///
+/// In replay mode, each request is answered with the first recorded response to an identical request
+/// that has not been replayed yet, or with the last one once all of them have been replayed.
+/// Requests that were never recorded fail with `CoCClientError::Cassette`.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+}
+
+impl Cassette {
+    /// Creates an empty cassette recording into the file at `path`, replacing it on the first response.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+
+    /// Loads the cassette at `path` for replay.
+    ///
+    /// # Returns
+    ///
+    /// Returns the cassette, or a `CoCClientError` if the file cannot be read or is not a cassette.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self, CoCClientError> {
+        let path = path.into();
+
+        let contents = std::fs::read_to_string(&path).map_err(|err| {
+            CoCClientError::Cassette(format!("cannot read {}: {}", path.display(), err))
+        })?;
+        let file: CassetteFile = serde_json::from_str(&contents).map_err(|err| {
+            CoCClientError::Cassette(format!("cannot parse {}: {}", path.display(), err))
+        })?;
+
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            state: Mutex::new(CassetteState {
+                replayed: vec![false; file.interactions.len()],
+                interactions: file.interactions,
+            }),
+        })
+    }
+
+    /// Returns the path of the cassette file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns whether the cassette records or replays requests.
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Returns the interactions recorded or loaded so far.
+    pub async fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().await.interactions.clone()
+    }
+
+    /// Appends an interaction and rewrites the cassette file, with `tokens` redacted.
+    pub(crate) async fn record_interaction(
+        &self,
+        mut interaction: Interaction,
+        tokens: &[&str],
+    ) -> Result<(), CoCClientError> {
+        interaction.response.body = redact(&interaction.response.body, tokens);
+        for (_, value) in &mut interaction.response.headers {
+            *value = redact(value, tokens);
+        }
+        if let Some(body) = &mut interaction.request.body {
+            *body = redact(body, tokens);
+        }
+
+        let mut state = self.state.lock().await;
+        state.interactions.push(interaction);
+        state.replayed.push(false);
+
+        let file = CassetteFile {
+            interactions: state.interactions.clone(),
+        };
+
+        self.write(&file).await.map_err(|err| {
+            CoCClientError::Cassette(format!("cannot write {}: {}", self.path.display(), err))
+        })
+    }
+
+    /// Returns the recorded response to `request`.
+    pub(crate) async fn replay_request(
+        &self,
+        request: &CassetteRequest,
+    ) -> Result<ClientResponse, CoCClientError> {
+        let mut state = self.state.lock().await;
+
+        let matching: Vec<usize> = state
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| interaction.request.matches(request))
+            .map(|(index, _)| index)
+            .collect();
+
+        let index = matching
+            .iter()
+            .copied()
+            .find(|&index| !state.replayed[index])
+            .or_else(|| matching.last().copied())
+            .ok_or_else(|| {
+                CoCClientError::Cassette(format!(
+                    "no recorded response to {} {} in {}",
+                    request.method,
+                    request.path,
+                    self.path.display()
+                ))
+            })?;
+
+        state.replayed[index] = true;
+
+        Ok(client_response(&state.interactions[index].response))
+    }
+
+    async fn write(&self, file: &CassetteFile) -> io::Result<()> {
+        if let Some(directory) = self
+            .path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            tokio::fs::create_dir_all(directory).await?;
+        }
+
+        let temporary = self.path.with_extension("tmp");
+        let contents = serde_json::to_vec_pretty(file)?;
+
+        tokio::fs::write(&temporary, contents).await?;
+        tokio::fs::rename(&temporary, &self.path).await
+    }
+}
+
+impl CassetteResponse {
+    pub(crate) fn new(status_code: StatusCode, headers: &HeaderMap, body: &str) -> Self {
+        Self {
+            status: status_code.as_u16(),
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body: body.to_string(),
+        }
+    }
+}
+
+fn client_response(response: &CassetteResponse) -> ClientResponse {
+    let headers = response
+        .headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((
+                HeaderName::from_bytes(name.as_bytes()).ok()?,
+                HeaderValue::from_str(value).ok()?,
+            ))
+        })
+        .collect();
+
+    ClientResponse {
+        text: response.body.clone(),
+        status_code: StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK),
+        headers,
+        stale: false,
+        deserialization_mode: Default::default(),
+        drift: None,
+    }
+}
+
+fn redact(text: &str, tokens: &[&str]) -> String {
+    tokens
+        .iter()
+        .filter(|token| !token.is_empty())
+        .fold(text.to_string(), |text, token| {
+            text.replace(token, REDACTED)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::client::CoCClient;
+    use crate::testing::{MockResponse, MockServer};
+
+    fn cassette_path(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        std::env::temp_dir().join(format!(
+            "rust_of_clans_{}_{}_{}.json",
+            name,
+            std::process::id(),
+            nanos
+        ))
+    }
+
+    #[tokio::test]
+    async fn recorded_requests_are_replayed_without_network() {
+        let server = MockServer::start_with_handler(|request| {
+            MockResponse::json(
+                200,
+                format!(
+                    r#"{{"startTime":"20230601T080000.000Z","endTime":"20230701T080000.000Z","echo":"{}"}}"#,
+                    request.header("authorization").unwrap_or_default()
+                ),
+            )
+            .header("Cache-Control", "max-age=600")
+        })
+        .await;
+        let path = cassette_path("record");
+
+        let recording = CoCClient::builder("MY_BEARER_TOKEN")
+            .base_url(server.url())
+            .cassette(Cassette::record(&path))
+            .build()
+            .unwrap();
+        let recorded = recording.get_current_gold_pass().await.unwrap();
+
+        let contents = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(!contents.contains("MY_BEARER_TOKEN"), "{}", contents);
+        assert!(contents.contains("Bearer REDACTED"), "{}", contents);
+
+        let replaying = CoCClient::builder("ANOTHER_TOKEN")
+            .base_url("http://127.0.0.1:9")
+            .cassette(Cassette::replay(&path).unwrap())
+            .build()
+            .unwrap();
+        let replayed = replaying.get_current_gold_pass_response().await.unwrap();
+
+        assert_eq!(replayed.data.start_time, recorded.start_time);
+        assert_eq!(
+            replayed.headers.get("cache-control").unwrap(),
+            "max-age=600"
+        );
+        assert_eq!(server.requests().len(), 1);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn identical_requests_are_replayed_in_recorded_order() {
+        let path = cassette_path("order");
+        let request = |limit: &str| {
+            CassetteRequest::new(
+                &Method::GET,
+                "/locations",
+                &[(String::from("limit"), String::from(limit))],
+                None,
+            )
+        };
+        let response = |body: &str| CassetteResponse {
+            status: 200,
+            headers: Vec::new(),
+            body: body.to_string(),
+        };
+
+        let cassette = Cassette::record(&path);
+        for (limit, body) in [("1", "first"), ("1", "second"), ("2", "other")] {
+            let interaction = Interaction {
+                request: request(limit),
+                response: response(body),
+            };
+            cassette.record_interaction(interaction, &[]).await.unwrap();
+        }
+
+        let cassette = Cassette::replay(&path).unwrap();
+        let mut bodies = Vec::new();
+        for limit in ["1", "1", "1", "2"] {
+            bodies.push(cassette.replay_request(&request(limit)).await.unwrap().text);
+        }
+
+        assert_eq!(bodies, vec!["first", "second", "second", "other"]);
+
+        let err = cassette.replay_request(&request("3")).await.unwrap_err();
+        assert!(matches!(err, CoCClientError::Cassette(_)), "{}", err);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[test]
+    fn missing_cassettes_cannot_be_replayed() {
+        let err = Cassette::replay(cassette_path("missing")).unwrap_err();
+
+        assert!(matches!(err, CoCClientError::Cassette(_)), "{}", err);
+    }
+}